@@ -0,0 +1,110 @@
+//! Embedded admin HTTP API for inspecting a running session: provider
+//! health/circuit state and a live tail of transcript events. Intended for
+//! an operator watching `tool_call`, `policy_decision`, and `error` events
+//! in real time without tailing the transcript file by hand.
+//!
+//! Gated behind the `admin-api` feature since it pulls in a small embedded
+//! HTTP server that most deployments won't need. Synchronous and blocking
+//! to match this crate's existing HTTP style (see `llm::Client`), so it
+//! just runs on its own background thread rather than requiring an async
+//! runtime.
+
+#![cfg(feature = "admin-api")]
+
+use crate::provider_health::ProviderHealthRegistry;
+use crate::transcript::TranscriptFeed;
+use std::io::Read;
+use std::sync::mpsc;
+use std::sync::Arc;
+
+/// Serve the admin API on `addr` until the process exits or the server
+/// errors. Meant to be run on a dedicated thread, e.g.
+/// `std::thread::spawn(move || admin_api::serve(...))`.
+pub fn serve(addr: &str, registry: Arc<ProviderHealthRegistry>, feed: TranscriptFeed) -> anyhow::Result<()> {
+    let server = tiny_http::Server::http(addr).map_err(|err| anyhow::anyhow!("{err}"))?;
+
+    for request in server.incoming_requests() {
+        let url = request.url().to_string();
+        if url == "/status" {
+            handle_status(request, &registry);
+        } else if let Some(rest) = url.strip_prefix("/transcript/tail") {
+            let session_id = parse_session_filter(rest);
+            let events = feed.subscribe(session_id);
+            std::thread::spawn(move || handle_tail(request, events));
+        } else {
+            let response = tiny_http::Response::from_string("not found").with_status_code(404);
+            let _ = request.respond(response);
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_status(request: tiny_http::Request, registry: &ProviderHealthRegistry) {
+    let health = registry.all_health_info();
+    let status: Vec<_> = health
+        .iter()
+        .map(|info| registry.get_status(&info.backend))
+        .collect();
+    let body = serde_json::json!({ "health": health, "status": status });
+    let payload = serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string());
+
+    let response = tiny_http::Response::from_string(payload).with_header(json_content_type());
+    let _ = request.respond(response);
+}
+
+/// Parse the `?session_id=...` filter from the raw query string suffix of
+/// `/transcript/tail`, so multiple concurrent sessions can be tailed
+/// independently by pointing each operator at a different URL.
+fn parse_session_filter(query_suffix: &str) -> Option<String> {
+    let query = query_suffix.strip_prefix('?')?;
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == "session_id").then(|| value.to_string())
+    })
+}
+
+fn handle_tail(request: tiny_http::Request, events: mpsc::Receiver<String>) {
+    let body = SseBody {
+        events,
+        pending: Vec::new(),
+    };
+    let headers = vec![
+        tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/event-stream"[..])
+            .expect("static header name/value"),
+        tiny_http::Header::from_bytes(&b"Cache-Control"[..], &b"no-cache"[..])
+            .expect("static header name/value"),
+    ];
+    let response = tiny_http::Response::new(tiny_http::StatusCode(200), headers, body, None, None);
+    let _ = request.respond(response);
+}
+
+fn json_content_type() -> tiny_http::Header {
+    tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header name/value")
+}
+
+/// Adapts a `Transcript` event feed into a `Read` stream of Server-Sent
+/// Events, one `data: <line>\n\n` frame per logged event. Blocks on
+/// `events.recv()` between frames, which is fine here since each HTTP
+/// connection is handled on its own thread.
+struct SseBody {
+    events: mpsc::Receiver<String>,
+    pending: Vec<u8>,
+}
+
+impl Read for SseBody {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            match self.events.recv() {
+                Ok(line) => self.pending = format!("data: {line}\n\n").into_bytes(),
+                Err(_) => return Ok(0),
+            }
+        }
+
+        let n = out.len().min(self.pending.len());
+        out[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+}
@@ -8,7 +8,7 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
@@ -22,6 +22,45 @@ pub enum CircuitState {
     HalfOpen,
 }
 
+/// How failures are detected and counted toward opening the circuit
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FailureDetection {
+    /// Open after `failure_threshold` consecutive failures (original behavior)
+    Consecutive,
+    /// Open once `failure_threshold` failures are observed within a rolling
+    /// time window, provided at least `min_requests` observations fell in
+    /// that window
+    SlidingWindow { window_secs: u64, min_requests: u32 },
+    /// Open once the failure fraction over a rolling window exceeds
+    /// `failure_threshold_fraction`, but only once `minimum_requests`
+    /// observations have landed in the window (a cold breaker can't trip on
+    /// a single failure)
+    Percentage {
+        window_secs: u64,
+        minimum_requests: u32,
+        /// Fraction in `[0.0, 1.0]`, e.g. `0.5` for 50% — not a 0-100 percentage
+        failure_threshold_fraction: f64,
+    },
+}
+
+impl Default for FailureDetection {
+    fn default() -> Self {
+        Self::Consecutive
+    }
+}
+
+impl FailureDetection {
+    /// The rolling window duration this mode tracks outcomes in, if any
+    fn window_secs(&self) -> Option<u64> {
+        match self {
+            Self::Consecutive => None,
+            Self::SlidingWindow { window_secs, .. } => Some(*window_secs),
+            Self::Percentage { window_secs, .. } => Some(*window_secs),
+        }
+    }
+}
+
 /// Configuration for circuit breaker behavior
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CircuitBreakerConfig {
@@ -37,6 +76,27 @@ pub struct CircuitBreakerConfig {
     /// Enable circuit breaker (can be disabled via config)
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// How failures are counted toward opening the circuit
+    #[serde(default)]
+    pub failure_detection: FailureDetection,
+    /// Multiplier applied to `recovery_timeout_secs` for each consecutive
+    /// half-open probe failure (1.0 = no backoff, preserving the fixed
+    /// recovery interval)
+    #[serde(default = "default_backoff_multiplier")]
+    pub backoff_multiplier: f64,
+    /// Upper bound on the backed-off recovery delay
+    #[serde(default = "default_max_recovery_secs")]
+    pub max_recovery_secs: u64,
+    /// Calls that take at least this long are treated as breaker failures
+    /// even when they return `Ok`, so a backend that's technically up but
+    /// unacceptably slow still trips the circuit. `None` disables
+    /// latency-based detection
+    #[serde(default = "default_slow_call_threshold_ms")]
+    pub slow_call_threshold_ms: Option<u64>,
+    /// Maximum number of probe requests allowed outstanding at once while
+    /// half-open, so a burst of traffic can't flood a recovering backend
+    #[serde(default = "default_half_open_max_concurrent")]
+    pub half_open_max_concurrent: u32,
 }
 
 fn default_failure_threshold() -> u32 {
@@ -51,6 +111,18 @@ fn default_half_open_probes() -> u32 {
 fn default_enabled() -> bool {
     true
 }
+fn default_backoff_multiplier() -> f64 {
+    1.0
+}
+fn default_max_recovery_secs() -> u64 {
+    300
+}
+fn default_slow_call_threshold_ms() -> Option<u64> {
+    None
+}
+fn default_half_open_max_concurrent() -> u32 {
+    1
+}
 
 impl Default for CircuitBreakerConfig {
     fn default() -> Self {
@@ -59,6 +131,11 @@ impl Default for CircuitBreakerConfig {
             recovery_timeout_secs: default_recovery_timeout_secs(),
             half_open_probes: default_half_open_probes(),
             enabled: default_enabled(),
+            failure_detection: FailureDetection::default(),
+            backoff_multiplier: default_backoff_multiplier(),
+            max_recovery_secs: default_max_recovery_secs(),
+            slow_call_threshold_ms: default_slow_call_threshold_ms(),
+            half_open_max_concurrent: default_half_open_max_concurrent(),
         }
     }
 }
@@ -73,6 +150,18 @@ struct CircuitBreakerState {
     total_failures: u64,
     total_successes: u64,
     total_rejections: u64,
+    /// Timestamped outcomes (`true` = failure) for `SlidingWindow` mode,
+    /// pruned to the configured window lazily on each access
+    window_outcomes: VecDeque<(Instant, bool)>,
+    /// Number of times a half-open probe has failed and reopened the
+    /// circuit since it last fully closed, used to back off the recovery delay
+    consecutive_open_cycles: u32,
+    /// Number of calls that exceeded `slow_call_threshold_ms`, tracked
+    /// separately from `total_failures` (a slow call counts as both)
+    slow_calls: u64,
+    /// Number of half-open probes currently outstanding, bounded by
+    /// `half_open_max_concurrent`
+    in_flight_probes: u32,
 }
 
 impl Default for CircuitBreakerState {
@@ -85,7 +174,63 @@ impl Default for CircuitBreakerState {
             total_failures: 0,
             total_successes: 0,
             total_rejections: 0,
+            window_outcomes: VecDeque::new(),
+            consecutive_open_cycles: 0,
+            slow_calls: 0,
+            in_flight_probes: 0,
+        }
+    }
+}
+
+impl CircuitBreakerState {
+    /// Drop outcomes older than `window_secs` and record a new one
+    fn push_window_outcome(&mut self, failure: bool, window_secs: u64) {
+        self.prune_window(window_secs);
+        self.window_outcomes.push_back((Instant::now(), failure));
+    }
+
+    fn prune_window(&mut self, window_secs: u64) {
+        let window = Duration::from_secs(window_secs);
+        while let Some((ts, _)) = self.window_outcomes.front() {
+            if ts.elapsed() > window {
+                self.window_outcomes.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// (total observations, failing observations) within the window, after pruning
+    fn window_counts(&mut self, window_secs: u64) -> (u32, u32) {
+        self.prune_window(window_secs);
+        let total = self.window_outcomes.len() as u32;
+        let failures = self.window_outcomes.iter().filter(|(_, f)| *f).count() as u32;
+        (total, failures)
+    }
+
+    /// Recovery delay after backing off for `consecutive_open_cycles` prior
+    /// probe failures, capped at `max_recovery_secs`
+    fn effective_recovery_delay(&self, config: &CircuitBreakerConfig) -> Duration {
+        let backed_off = config.recovery_timeout_secs as f64
+            * config.backoff_multiplier.powi(self.consecutive_open_cycles as i32);
+        let capped = backed_off.min(config.max_recovery_secs as f64).max(0.0);
+        Duration::from_secs_f64(capped)
+    }
+
+    /// Same as `window_counts`, but read-only (for use from `stats()`, which
+    /// only takes a read lock and can tolerate slightly stale pruning)
+    fn window_counts_snapshot(&self, window_secs: u64) -> (u32, u32) {
+        let window = Duration::from_secs(window_secs);
+        let live = self.window_outcomes.iter().filter(|(ts, _)| ts.elapsed() <= window);
+        let mut total = 0u32;
+        let mut failures = 0u32;
+        for (_, failure) in live {
+            total += 1;
+            if *failure {
+                failures += 1;
+            }
         }
+        (total, failures)
     }
 }
 
@@ -100,12 +245,59 @@ pub enum CircuitBreakerDecision {
     Probe,
 }
 
+/// Classifies whether an error observed by `record_outcome`/`call` should
+/// count as a breaker failure. Defaults to treating every error as a
+/// failure; implement this to let expected/benign errors (e.g. a 404) pass
+/// through without tripping the circuit.
+pub trait FailurePredicate: Send + Sync {
+    fn is_failure(&self, err: &dyn std::error::Error) -> bool;
+}
+
+/// Default predicate: any error counts as a failure
+struct AnyErrorIsFailure;
+
+impl FailurePredicate for AnyErrorIsFailure {
+    fn is_failure(&self, _err: &dyn std::error::Error) -> bool {
+        true
+    }
+}
+
+/// Notified of state transitions and rejections, so a breaker's activity can
+/// be fed into metrics or structured logging instead of stderr. All methods
+/// default to doing nothing, so implementors only override what they need.
+pub trait CircuitBreakerObserver: Send + Sync {
+    /// The circuit just opened (or reopened after a failed half-open probe)
+    fn on_open(&self, _name: &str, _stats: &CircuitBreakerStats) {}
+    /// The circuit just entered half-open to probe recovery
+    fn on_half_open(&self, _name: &str, _stats: &CircuitBreakerStats) {}
+    /// The circuit just closed after enough successful probes
+    fn on_close(&self, _name: &str, _stats: &CircuitBreakerStats) {}
+    /// A request was rejected because the circuit is open
+    fn on_rejected(&self, _name: &str, _stats: &CircuitBreakerStats) {}
+}
+
+/// Default observer: does nothing
+struct NoopObserver;
+
+impl CircuitBreakerObserver for NoopObserver {}
+
 /// A single circuit breaker instance
-#[derive(Debug)]
 pub struct CircuitBreaker {
     name: String,
     config: CircuitBreakerConfig,
     state: RwLock<CircuitBreakerState>,
+    predicate: Arc<dyn FailurePredicate>,
+    observer: Arc<dyn CircuitBreakerObserver>,
+}
+
+impl std::fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("name", &self.name)
+            .field("config", &self.config)
+            .field("state", &self.state)
+            .finish()
+    }
 }
 
 impl CircuitBreaker {
@@ -115,6 +307,44 @@ impl CircuitBreaker {
             name: name.to_string(),
             config,
             state: RwLock::new(CircuitBreakerState::default()),
+            predicate: Arc::new(AnyErrorIsFailure),
+            observer: Arc::new(NoopObserver),
+        }
+    }
+
+    /// Use a custom predicate to classify which errors count as failures
+    pub fn with_predicate(mut self, predicate: Arc<dyn FailurePredicate>) -> Self {
+        self.predicate = predicate;
+        self
+    }
+
+    /// Use a custom observer to react to state transitions and rejections
+    pub fn with_observer(mut self, observer: Arc<dyn CircuitBreakerObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Snapshot stats from an already-locked state, to avoid re-locking
+    /// `self.state` (which `stats()` takes a read lock on) while a caller is
+    /// still holding the write guard
+    fn stats_from(&self, state: &CircuitBreakerState) -> CircuitBreakerStats {
+        let measured_failure_rate = self.config.failure_detection.window_secs().map(|w| {
+            let (total, failures) = state.window_counts_snapshot(w);
+            if total == 0 {
+                0.0
+            } else {
+                failures as f64 / total as f64
+            }
+        });
+        CircuitBreakerStats {
+            name: self.name.clone(),
+            state: state.state,
+            consecutive_failures: state.consecutive_failures,
+            total_failures: state.total_failures,
+            total_successes: state.total_successes,
+            total_rejections: state.total_rejections,
+            measured_failure_rate,
+            slow_calls: state.slow_calls,
         }
     }
 
@@ -132,26 +362,79 @@ impl CircuitBreaker {
         let mut state = self.state.write().unwrap();
 
         match state.state {
-            CircuitState::Closed => CircuitBreakerDecision::Allow,
+            CircuitState::Closed => {
+                match self.config.failure_detection {
+                    FailureDetection::Consecutive => {}
+                    FailureDetection::SlidingWindow {
+                        window_secs,
+                        min_requests,
+                    } => {
+                        let (total, failures) = state.window_counts(window_secs);
+                        if total >= min_requests && failures >= self.config.failure_threshold {
+                            state.state = CircuitState::Open;
+                            state.last_failure_time = Some(Instant::now());
+                            let stats = self.stats_from(&state);
+                            self.observer.on_open(&self.name, &stats);
+                        }
+                    }
+                    FailureDetection::Percentage {
+                        window_secs,
+                        minimum_requests,
+                        failure_threshold_fraction,
+                    } => {
+                        let (total, failures) = state.window_counts(window_secs);
+                        // Below the minimum observed volume, always allow regardless of ratio
+                        if total >= minimum_requests {
+                            let rate = failures as f64 / total as f64;
+                            if rate > failure_threshold_fraction {
+                                state.state = CircuitState::Open;
+                                state.last_failure_time = Some(Instant::now());
+                                let stats = self.stats_from(&state);
+                                self.observer.on_open(&self.name, &stats);
+                            }
+                        }
+                    }
+                }
+                match state.state {
+                    CircuitState::Closed => CircuitBreakerDecision::Allow,
+                    _ => {
+                        state.total_rejections += 1;
+                        let stats = self.stats_from(&state);
+                        self.observer.on_rejected(&self.name, &stats);
+                        CircuitBreakerDecision::Reject
+                    }
+                }
+            }
             CircuitState::Open => {
-                // Check if recovery timeout has passed
+                // Check if recovery timeout (backed off by prior probe failures) has passed
                 if let Some(last_failure) = state.last_failure_time {
-                    let recovery_duration = Duration::from_secs(self.config.recovery_timeout_secs);
+                    let recovery_duration = state.effective_recovery_delay(&self.config);
                     if last_failure.elapsed() >= recovery_duration {
-                        // Transition to half-open
+                        // Transition to half-open, admitting this request as the first probe
                         state.state = CircuitState::HalfOpen;
                         state.consecutive_successes = 0;
-                        eprintln!(
-                            "[circuit_breaker:{}] Transitioning to half-open after {}s recovery",
-                            self.name, self.config.recovery_timeout_secs
-                        );
+                        state.in_flight_probes = 1;
+                        let stats = self.stats_from(&state);
+                        self.observer.on_half_open(&self.name, &stats);
                         return CircuitBreakerDecision::Probe;
                     }
                 }
                 state.total_rejections += 1;
+                let stats = self.stats_from(&state);
+                self.observer.on_rejected(&self.name, &stats);
                 CircuitBreakerDecision::Reject
             }
-            CircuitState::HalfOpen => CircuitBreakerDecision::Probe,
+            CircuitState::HalfOpen => {
+                if state.in_flight_probes < self.config.half_open_max_concurrent {
+                    state.in_flight_probes += 1;
+                    CircuitBreakerDecision::Probe
+                } else {
+                    state.total_rejections += 1;
+                    let stats = self.stats_from(&state);
+                    self.observer.on_rejected(&self.name, &stats);
+                    CircuitBreakerDecision::Reject
+                }
+            }
         }
     }
 
@@ -165,25 +448,32 @@ impl CircuitBreaker {
         state.total_successes += 1;
         state.consecutive_failures = 0;
 
+        if let Some(window_secs) = self.config.failure_detection.window_secs() {
+            state.push_window_outcome(false, window_secs);
+        }
+
         match state.state {
             CircuitState::Closed => {
                 // Already closed, nothing to do
             }
             CircuitState::HalfOpen => {
+                state.in_flight_probes = state.in_flight_probes.saturating_sub(1);
                 state.consecutive_successes += 1;
                 if state.consecutive_successes >= self.config.half_open_probes {
                     // Close the circuit
                     state.state = CircuitState::Closed;
                     state.consecutive_successes = 0;
-                    eprintln!(
-                        "[circuit_breaker:{}] Circuit closed after {} successful probes",
-                        self.name, self.config.half_open_probes
-                    );
+                    state.consecutive_open_cycles = 0;
+                    state.in_flight_probes = 0;
+                    let stats = self.stats_from(&state);
+                    self.observer.on_close(&self.name, &stats);
                 }
             }
             CircuitState::Open => {
                 // Shouldn't happen, but close circuit on success
                 state.state = CircuitState::Closed;
+                state.consecutive_open_cycles = 0;
+                state.in_flight_probes = 0;
             }
         }
     }
@@ -200,23 +490,30 @@ impl CircuitBreaker {
         state.consecutive_successes = 0;
         state.last_failure_time = Some(Instant::now());
 
+        if let Some(window_secs) = self.config.failure_detection.window_secs() {
+            state.push_window_outcome(true, window_secs);
+        }
+
         match state.state {
             CircuitState::Closed => {
-                if state.consecutive_failures >= self.config.failure_threshold {
+                // Only the Consecutive mode opens here; SlidingWindow/Percentage
+                // have their own volume-gated checks in `check()` and must not
+                // also trip on the raw consecutive count.
+                if matches!(self.config.failure_detection, FailureDetection::Consecutive)
+                    && state.consecutive_failures >= self.config.failure_threshold
+                {
                     state.state = CircuitState::Open;
-                    eprintln!(
-                        "[circuit_breaker:{}] Circuit opened after {} consecutive failures",
-                        self.name, state.consecutive_failures
-                    );
+                    let stats = self.stats_from(&state);
+                    self.observer.on_open(&self.name, &stats);
                 }
             }
             CircuitState::HalfOpen => {
-                // Failure in half-open immediately reopens
+                // Failure in half-open immediately reopens, backing off the next recovery wait
                 state.state = CircuitState::Open;
-                eprintln!(
-                    "[circuit_breaker:{}] Circuit reopened after probe failure",
-                    self.name
-                );
+                state.consecutive_open_cycles += 1;
+                state.in_flight_probes = 0;
+                let stats = self.stats_from(&state);
+                self.observer.on_open(&self.name, &stats);
             }
             CircuitState::Open => {
                 // Already open, just track the failure
@@ -227,17 +524,107 @@ impl CircuitBreaker {
     /// Get statistics for this circuit breaker
     pub fn stats(&self) -> CircuitBreakerStats {
         let state = self.state.read().unwrap();
-        CircuitBreakerStats {
-            name: self.name.clone(),
-            state: state.state,
-            consecutive_failures: state.consecutive_failures,
-            total_failures: state.total_failures,
-            total_successes: state.total_successes,
-            total_rejections: state.total_rejections,
+        self.stats_from(&state)
+    }
+
+    /// Record the outcome of an operation already performed elsewhere,
+    /// classifying errors via the breaker's `FailurePredicate`. An `Ok`
+    /// resets failure counters as usual; an `Err` the predicate doesn't
+    /// consider a failure is passed through without touching breaker state
+    /// at all (it's neither a success nor a failure).
+    pub fn record_outcome<T, E: std::error::Error>(&self, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self.record_success(),
+            Err(e) => {
+                if self.predicate.is_failure(e) {
+                    self.record_failure();
+                }
+            }
+        }
+    }
+
+    /// Like `record_outcome`, but also treats the call as a breaker failure
+    /// (and bumps `slow_calls`) if `elapsed` meets or exceeds
+    /// `slow_call_threshold_ms`, even when `result` is `Ok`
+    pub fn record_timed_outcome<T, E: std::error::Error>(
+        &self,
+        result: &Result<T, E>,
+        elapsed: Duration,
+    ) {
+        let is_slow = self
+            .config
+            .slow_call_threshold_ms
+            .is_some_and(|threshold_ms| elapsed >= Duration::from_millis(threshold_ms));
+
+        if is_slow {
+            self.state.write().unwrap().slow_calls += 1;
+        }
+
+        if is_slow {
+            self.record_failure();
+        } else {
+            self.record_outcome(result);
+        }
+    }
+
+    /// Run `f`, automatically performing the `check()`/record dance: reject
+    /// without calling `f` if the circuit is open, otherwise record success
+    /// or failure (per the breaker's `FailurePredicate`) based on the
+    /// outcome, also counting the call as a failure if it ran slower than
+    /// `slow_call_threshold_ms`
+    pub fn call<F, T, E>(&self, f: F) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: FnOnce() -> Result<T, E>,
+        E: std::error::Error,
+    {
+        if self.check() == CircuitBreakerDecision::Reject {
+            return Err(CircuitBreakerError::Rejected);
+        }
+
+        let started = Instant::now();
+        let result = f();
+        self.record_timed_outcome(&result, started.elapsed());
+        result.map_err(CircuitBreakerError::Inner)
+    }
+
+    /// Async variant of `call` that awaits a future produced by `f`
+    pub async fn call_async<F, Fut, T, E>(&self, f: F) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+        E: std::error::Error,
+    {
+        if self.check() == CircuitBreakerDecision::Reject {
+            return Err(CircuitBreakerError::Rejected);
+        }
+
+        let started = Instant::now();
+        let result = f().await;
+        self.record_timed_outcome(&result, started.elapsed());
+        result.map_err(CircuitBreakerError::Inner)
+    }
+}
+
+/// Error returned by `CircuitBreaker::call`/`call_async`
+#[derive(Debug)]
+pub enum CircuitBreakerError<E> {
+    /// The circuit was open and `f` was never called
+    Rejected,
+    /// `f` ran and returned an error
+    Inner(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for CircuitBreakerError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rejected => write!(f, "request rejected by open circuit breaker"),
+            Self::Inner(e) => write!(f, "{}", e),
         }
     }
 }
 
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for CircuitBreakerError<E> {}
+
 /// Statistics for a circuit breaker
 #[derive(Debug, Clone, Serialize)]
 pub struct CircuitBreakerStats {
@@ -246,22 +633,48 @@ pub struct CircuitBreakerStats {
     pub consecutive_failures: u32,
     pub total_failures: u64,
     pub total_successes: u64,
+    /// Current failure fraction over the rolling window, for
+    /// `SlidingWindow`/`Percentage` detection modes; `None` for `Consecutive`
+    pub measured_failure_rate: Option<f64>,
     pub total_rejections: u64,
+    /// Calls that exceeded `slow_call_threshold_ms`
+    pub slow_calls: u64,
 }
 
 /// Registry of circuit breakers per backend
-#[derive(Debug)]
 pub struct CircuitBreakerRegistry {
     config: CircuitBreakerConfig,
     breakers: Arc<RwLock<HashMap<String, Arc<CircuitBreaker>>>>,
+    /// Applied to every breaker this registry creates, so a single
+    /// registry-wide instrumentation point can feed a metrics exporter or
+    /// tracing subscriber
+    observer: Option<Arc<dyn CircuitBreakerObserver>>,
+}
+
+impl std::fmt::Debug for CircuitBreakerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreakerRegistry")
+            .field("config", &self.config)
+            .field("breakers", &self.breakers)
+            .finish()
+    }
 }
 
 impl CircuitBreakerRegistry {
-    /// Create a new registry with the given config
+    /// Create a new registry with the given config and no observer
     pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self::with_observer(config, None)
+    }
+
+    /// Create a new registry whose breakers all report to `observer`
+    pub fn with_observer(
+        config: CircuitBreakerConfig,
+        observer: Option<Arc<dyn CircuitBreakerObserver>>,
+    ) -> Self {
         Self {
             config,
             breakers: Arc::new(RwLock::new(HashMap::new())),
+            observer,
         }
     }
 
@@ -280,7 +693,11 @@ impl CircuitBreakerRegistry {
             return cb.clone();
         }
 
-        let cb = Arc::new(CircuitBreaker::new(backend, self.config.clone()));
+        let mut cb = CircuitBreaker::new(backend, self.config.clone());
+        if let Some(observer) = &self.observer {
+            cb = cb.with_observer(observer.clone());
+        }
+        let cb = Arc::new(cb);
         breakers.insert(backend.to_string(), cb.clone());
         cb
     }
@@ -300,6 +717,29 @@ impl CircuitBreakerRegistry {
         self.get(backend).record_failure();
     }
 
+    /// Run `f` through the named backend's circuit breaker (see `CircuitBreaker::call`)
+    pub fn call<F, T, E>(&self, backend: &str, f: F) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: FnOnce() -> Result<T, E>,
+        E: std::error::Error,
+    {
+        self.get(backend).call(f)
+    }
+
+    /// Async variant of `call` (see `CircuitBreaker::call_async`)
+    pub async fn call_async<F, Fut, T, E>(
+        &self,
+        backend: &str,
+        f: F,
+    ) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+        E: std::error::Error,
+    {
+        self.get(backend).call_async(f).await
+    }
+
     /// Get statistics for all circuit breakers
     pub fn all_stats(&self) -> Vec<CircuitBreakerStats> {
         let breakers = self.breakers.read().unwrap();
@@ -346,6 +786,11 @@ mod tests {
             recovery_timeout_secs: 30,
             half_open_probes: 2,
             enabled: true,
+            failure_detection: FailureDetection::default(),
+            backoff_multiplier: 1.0,
+            max_recovery_secs: 300,
+            slow_call_threshold_ms: None,
+            half_open_max_concurrent: 1,
         };
         let cb = CircuitBreaker::new("test", config);
 
@@ -368,6 +813,11 @@ mod tests {
             recovery_timeout_secs: 30,
             half_open_probes: 2,
             enabled: true,
+            failure_detection: FailureDetection::default(),
+            backoff_multiplier: 1.0,
+            max_recovery_secs: 300,
+            slow_call_threshold_ms: None,
+            half_open_max_concurrent: 1,
         };
         let cb = CircuitBreaker::new("test", config);
 
@@ -405,6 +855,11 @@ mod tests {
             recovery_timeout_secs: 0, // Immediate recovery for testing
             half_open_probes: 2,
             enabled: true,
+            failure_detection: FailureDetection::default(),
+            backoff_multiplier: 1.0,
+            max_recovery_secs: 300,
+            slow_call_threshold_ms: None,
+            half_open_max_concurrent: 1,
         };
         let cb = CircuitBreaker::new("test", config);
 
@@ -435,6 +890,11 @@ mod tests {
             recovery_timeout_secs: 0,
             half_open_probes: 2,
             enabled: true,
+            failure_detection: FailureDetection::default(),
+            backoff_multiplier: 1.0,
+            max_recovery_secs: 300,
+            slow_call_threshold_ms: None,
+            half_open_max_concurrent: 1,
         };
         let cb = CircuitBreaker::new("test", config);
 
@@ -449,6 +909,31 @@ mod tests {
         assert_eq!(cb.state(), CircuitState::Open);
     }
 
+    #[test]
+    fn test_half_open_bounds_concurrent_probes() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            recovery_timeout_secs: 0,
+            half_open_probes: 5,
+            half_open_max_concurrent: 2,
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        cb.record_failure();
+        std::thread::sleep(Duration::from_millis(10));
+
+        // First two checks are admitted as probes, up to the concurrency limit
+        assert_eq!(cb.check(), CircuitBreakerDecision::Probe);
+        assert_eq!(cb.check(), CircuitBreakerDecision::Probe);
+        // A third concurrent probe is rejected rather than flooding the backend
+        assert_eq!(cb.check(), CircuitBreakerDecision::Reject);
+
+        // Once a probe completes, its slot frees up for the next check
+        cb.record_success();
+        assert_eq!(cb.check(), CircuitBreakerDecision::Probe);
+    }
+
     #[test]
     fn test_registry_per_backend() {
         let registry = CircuitBreakerRegistry::default();
@@ -482,4 +967,321 @@ mod tests {
         assert_eq!(stats.total_failures, 1);
         assert_eq!(stats.state, CircuitState::Closed);
     }
+
+    #[test]
+    fn test_sliding_window_trips_on_interleaved_failures() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 4,
+            failure_detection: FailureDetection::SlidingWindow {
+                window_secs: 60,
+                min_requests: 5,
+            },
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        // Interleaved failures and successes never trip consecutive-counting,
+        // but the window only cares about the error count within it.
+        cb.record_failure();
+        cb.record_failure();
+        cb.record_success();
+        cb.record_failure();
+        cb.record_failure();
+
+        // Not evaluated until the next check(), since pruning/tripping happens on access
+        assert_eq!(cb.state(), CircuitState::Closed);
+        assert_eq!(cb.check(), CircuitBreakerDecision::Reject);
+        assert_eq!(cb.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_sliding_window_requires_min_requests() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            failure_detection: FailureDetection::SlidingWindow {
+                window_secs: 60,
+                min_requests: 5,
+            },
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        cb.record_failure();
+        cb.record_failure();
+
+        // Below min_requests, so the circuit stays closed regardless of failure count
+        assert_eq!(cb.check(), CircuitBreakerDecision::Allow);
+    }
+
+    #[test]
+    fn test_percentage_mode_requires_minimum_volume() {
+        let config = CircuitBreakerConfig {
+            failure_detection: FailureDetection::Percentage {
+                window_secs: 60,
+                minimum_requests: 10,
+                failure_threshold_fraction: 0.5,
+            },
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        // 100% failures, but below minimum_requests -> always Allow
+        for _ in 0..4 {
+            cb.record_failure();
+        }
+        assert_eq!(cb.check(), CircuitBreakerDecision::Allow);
+    }
+
+    #[test]
+    fn test_percentage_mode_low_failure_threshold_still_requires_minimum_volume() {
+        // A low failure_threshold must not let record_failure's own
+        // consecutive-count check (meant only for Consecutive mode) open the
+        // circuit before minimum_requests observations have landed.
+        let config = CircuitBreakerConfig {
+            failure_threshold: 3,
+            failure_detection: FailureDetection::Percentage {
+                window_secs: 60,
+                minimum_requests: 10,
+                failure_threshold_fraction: 0.5,
+            },
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        // 3 consecutive failures would trip Consecutive mode at this
+        // threshold, but Percentage mode must stay closed below minimum_requests
+        cb.record_failure();
+        cb.record_failure();
+        cb.record_failure();
+
+        assert_eq!(cb.state(), CircuitState::Closed);
+        assert_eq!(cb.check(), CircuitBreakerDecision::Allow);
+    }
+
+    #[test]
+    fn test_percentage_mode_trips_on_rate() {
+        let config = CircuitBreakerConfig {
+            failure_detection: FailureDetection::Percentage {
+                window_secs: 60,
+                minimum_requests: 4,
+                failure_threshold_fraction: 0.5,
+            },
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        cb.record_success();
+        cb.record_failure();
+        cb.record_failure();
+        cb.record_failure();
+
+        // 3/4 = 75% > 50% threshold, with enough volume
+        assert_eq!(cb.check(), CircuitBreakerDecision::Reject);
+        assert_eq!(cb.state(), CircuitState::Open);
+
+        let stats = cb.stats();
+        assert_eq!(stats.measured_failure_rate, Some(0.75));
+    }
+
+    #[test]
+    fn test_call_records_success_and_failure() {
+        let cb = CircuitBreaker::new("test", CircuitBreakerConfig::default());
+
+        let ok: Result<u32, CircuitBreakerError<std::io::Error>> = cb.call(|| Ok(42));
+        assert_eq!(ok.unwrap(), 42);
+        assert_eq!(cb.stats().total_successes, 1);
+
+        let err: Result<u32, CircuitBreakerError<std::io::Error>> =
+            cb.call(|| Err(std::io::Error::other("boom")));
+        assert!(err.is_err());
+        assert_eq!(cb.stats().total_failures, 1);
+    }
+
+    #[test]
+    fn test_slow_successful_call_counts_as_failure() {
+        let config = CircuitBreakerConfig {
+            slow_call_threshold_ms: Some(10),
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        let result: Result<u32, CircuitBreakerError<std::io::Error>> = cb.call(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            Ok(42)
+        });
+
+        // The call itself succeeded...
+        assert_eq!(result.unwrap(), 42);
+        // ...but it was too slow, so the breaker still counts it as a failure
+        let stats = cb.stats();
+        assert_eq!(stats.total_failures, 1);
+        assert_eq!(stats.total_successes, 0);
+        assert_eq!(stats.slow_calls, 1);
+    }
+
+    #[test]
+    fn test_fast_call_is_not_counted_as_slow() {
+        let config = CircuitBreakerConfig {
+            slow_call_threshold_ms: Some(1000),
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        let result: Result<u32, CircuitBreakerError<std::io::Error>> = cb.call(|| Ok(42));
+
+        assert_eq!(result.unwrap(), 42);
+        let stats = cb.stats();
+        assert_eq!(stats.total_successes, 1);
+        assert_eq!(stats.slow_calls, 0);
+    }
+
+    #[test]
+    fn test_call_rejects_without_invoking_closure_when_open() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+
+        let mut invoked = false;
+        let result: Result<(), CircuitBreakerError<std::io::Error>> = cb.call(|| {
+            invoked = true;
+            Ok(())
+        });
+
+        assert!(!invoked);
+        assert!(matches!(result, Err(CircuitBreakerError::Rejected)));
+    }
+
+    struct OnlyTimeoutsFail;
+
+    impl FailurePredicate for OnlyTimeoutsFail {
+        fn is_failure(&self, err: &dyn std::error::Error) -> bool {
+            err.to_string().contains("timeout")
+        }
+    }
+
+    #[test]
+    fn test_predicate_ignores_non_matching_errors() {
+        let cb = CircuitBreaker::new("test", CircuitBreakerConfig::default())
+            .with_predicate(Arc::new(OnlyTimeoutsFail));
+
+        let not_found: Result<(), std::io::Error> = Err(std::io::Error::other("404 not found"));
+        cb.record_outcome(&not_found);
+
+        // Not classified as a failure, so breaker state is untouched
+        let stats = cb.stats();
+        assert_eq!(stats.total_failures, 0);
+        assert_eq!(stats.total_successes, 0);
+
+        let timeout: Result<(), std::io::Error> = Err(std::io::Error::other("connect timeout"));
+        cb.record_outcome(&timeout);
+
+        assert_eq!(cb.stats().total_failures, 1);
+    }
+
+    #[test]
+    fn test_exponential_backoff_grows_and_caps_recovery_delay() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            recovery_timeout_secs: 10,
+            backoff_multiplier: 4.0,
+            max_recovery_secs: 50,
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        cb.record_failure(); // opens, 0 prior cycles -> delay = 10s
+        let state = cb.state.read().unwrap();
+        assert_eq!(state.effective_recovery_delay(&cb.config), Duration::from_secs(10));
+        drop(state);
+
+        // Probe fails once half-open -> 1 cycle -> delay = 10 * 4^1 = 40s
+        cb.state.write().unwrap().state = CircuitState::HalfOpen; // force for this unit test
+        cb.record_failure();
+        let state = cb.state.read().unwrap();
+        assert_eq!(state.consecutive_open_cycles, 1);
+        assert_eq!(state.effective_recovery_delay(&cb.config), Duration::from_secs(40));
+        drop(state);
+
+        // Another failed probe -> 2 cycles -> 10 * 4^2 = 160s, capped to 50s
+        cb.state.write().unwrap().state = CircuitState::HalfOpen;
+        cb.record_failure();
+        let state = cb.state.read().unwrap();
+        assert_eq!(state.consecutive_open_cycles, 2);
+        assert_eq!(state.effective_recovery_delay(&cb.config), Duration::from_secs(50));
+    }
+
+    #[derive(Default)]
+    struct SpyObserver {
+        events: std::sync::Mutex<Vec<&'static str>>,
+    }
+
+    impl CircuitBreakerObserver for SpyObserver {
+        fn on_open(&self, _name: &str, _stats: &CircuitBreakerStats) {
+            self.events.lock().unwrap().push("open");
+        }
+        fn on_half_open(&self, _name: &str, _stats: &CircuitBreakerStats) {
+            self.events.lock().unwrap().push("half_open");
+        }
+        fn on_close(&self, _name: &str, _stats: &CircuitBreakerStats) {
+            self.events.lock().unwrap().push("close");
+        }
+        fn on_rejected(&self, _name: &str, _stats: &CircuitBreakerStats) {
+            self.events.lock().unwrap().push("rejected");
+        }
+    }
+
+    #[test]
+    fn test_observer_is_notified_of_each_transition() {
+        let spy = Arc::new(SpyObserver::default());
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            recovery_timeout_secs: 0,
+            half_open_probes: 1,
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config).with_observer(spy.clone());
+
+        cb.record_failure(); // opens
+        cb.check(); // recovery timeout is 0s, so this immediately probes
+        cb.record_success(); // closes after 1 probe
+
+        assert_eq!(
+            *spy.events.lock().unwrap(),
+            vec!["open", "half_open", "close"]
+        );
+    }
+
+    #[test]
+    fn test_observer_is_notified_of_rejections() {
+        let spy = Arc::new(SpyObserver::default());
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new("test", config).with_observer(spy.clone());
+
+        cb.record_failure(); // opens, recovery_timeout_secs defaults well above 0
+        cb.check(); // still within recovery window -> rejected
+
+        assert_eq!(*spy.events.lock().unwrap(), vec!["open", "rejected"]);
+    }
+
+    #[test]
+    fn test_registry_with_observer_applies_it_to_every_breaker() {
+        let spy = Arc::new(SpyObserver::default());
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        };
+        let registry = CircuitBreakerRegistry::with_observer(config, Some(spy.clone()));
+
+        registry.record_failure("backend-a");
+        registry.record_failure("backend-b");
+
+        assert_eq!(*spy.events.lock().unwrap(), vec!["open", "open"]);
+    }
 }
@@ -0,0 +1,46 @@
+//! Configuration types shared across the crate.
+//!
+//! NOTE: this snapshot doesn't carry the rest of the configuration surface
+//! (CLI flags, persona/tool settings, etc.) that a real `config.rs` would
+//! have - only `ContextConfig`, which `context.rs` depends on, is
+//! reconstructed here.
+
+/// How context size is measured against `ContextConfig`'s limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountMode {
+    /// Raw character counts (`String::len`). Cheap, but drifts from what
+    /// the model actually sees.
+    #[default]
+    Chars,
+    /// Real BPE token counts via the encoding named by `ContextConfig::model`.
+    Tokens,
+}
+
+/// Configuration for conversation context management and compaction.
+#[derive(Debug, Clone)]
+pub struct ContextConfig {
+    /// How to measure context usage: characters (fallback) or tokens.
+    pub count_mode: CountMode,
+    /// Model/encoding name used to select a BPE tokenizer when
+    /// `count_mode` is `CountMode::Tokens`.
+    pub model: String,
+    pub max_chars: usize,
+    pub max_tokens: usize,
+    pub auto_compact_enabled: bool,
+    pub auto_compact_threshold: f64,
+    pub keep_last_turns: usize,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            count_mode: CountMode::Chars,
+            model: "gpt-4o".to_string(),
+            max_chars: 100_000,
+            max_tokens: 128_000,
+            auto_compact_enabled: true,
+            auto_compact_threshold: 0.8,
+            keep_last_turns: 10,
+        }
+    }
+}
@@ -8,8 +8,11 @@
 
 #![allow(dead_code)]
 
-use crate::config::ContextConfig;
+use crate::config::{ContextConfig, CountMode};
+use crate::conversation_store::{ConversationId, ConversationStore};
+use anyhow::Result;
 use serde_json::Value;
+use tiktoken_rs::CoreBPE;
 
 /// Statistics about current context usage
 #[derive(Debug, Clone)]
@@ -18,8 +21,14 @@ pub struct ContextStats {
     pub summary_chars: usize,
     pub messages_chars: usize,
     pub total_chars: usize,
+    pub system_prompt_tokens: usize,
+    pub summary_tokens: usize,
+    pub messages_tokens: usize,
+    pub total_tokens: usize,
     pub message_count: usize,
     pub max_chars: usize,
+    pub max_tokens: usize,
+    /// Usage ratio against whichever limit `ContextConfig::count_mode` selects
     pub usage_ratio: f64,
     pub has_summary: bool,
 }
@@ -29,6 +38,8 @@ pub struct ContextStats {
 pub struct CompactionResult {
     pub chars_before: usize,
     pub chars_after: usize,
+    pub tokens_before: usize,
+    pub tokens_after: usize,
     pub messages_before: usize,
     pub messages_after: usize,
 }
@@ -43,21 +54,38 @@ pub struct ConversationContext {
     pub messages: Vec<Value>,
     /// Configuration
     pub config: ContextConfig,
+    /// BPE encoder for `config.model`, built once when `count_mode` is
+    /// `CountMode::Tokens`; `None` keeps the char-only fast path allocation-free
+    encoder: Option<CoreBPE>,
+    /// Per-message token counts, cached alongside `messages` so `stats()`
+    /// doesn't re-tokenize the whole history on every call
+    message_token_counts: Vec<usize>,
 }
 
 impl ConversationContext {
     /// Create a new context with the given system prompt and config
     pub fn new(system_prompt: String, config: ContextConfig) -> Self {
+        let encoder = match config.count_mode {
+            CountMode::Tokens => Some(encoder_for_model(&config.model)),
+            CountMode::Chars => None,
+        };
+
         Self {
             system_prompt,
             summary_message: None,
             messages: Vec::new(),
             config,
+            encoder,
+            message_token_counts: Vec::new(),
         }
     }
 
     /// Add a message to the context
     pub fn push_message(&mut self, message: Value) {
+        if let Some(encoder) = &self.encoder {
+            self.message_token_counts
+                .push(count_message_tokens(encoder, &message));
+        }
         self.messages.push(message);
     }
 
@@ -68,14 +96,37 @@ impl ConversationContext {
         let messages_chars: usize = self.messages.iter().map(estimate_message_chars).sum();
         let total_chars = system_prompt_chars + summary_chars + messages_chars;
 
+        let (system_prompt_tokens, summary_tokens, messages_tokens) = match &self.encoder {
+            Some(encoder) => (
+                encoder.encode_ordinary(&self.system_prompt).len(),
+                self.summary_message
+                    .as_ref()
+                    .map(|s| encoder.encode_ordinary(s).len())
+                    .unwrap_or(0),
+                self.message_token_counts.iter().sum(),
+            ),
+            None => (0, 0, 0),
+        };
+        let total_tokens = system_prompt_tokens + summary_tokens + messages_tokens;
+
+        let usage_ratio = match self.config.count_mode {
+            CountMode::Tokens => total_tokens as f64 / self.config.max_tokens as f64,
+            CountMode::Chars => total_chars as f64 / self.config.max_chars as f64,
+        };
+
         ContextStats {
             system_prompt_chars,
             summary_chars,
             messages_chars,
             total_chars,
+            system_prompt_tokens,
+            summary_tokens,
+            messages_tokens,
+            total_tokens,
             message_count: self.messages.len(),
             max_chars: self.config.max_chars,
-            usage_ratio: total_chars as f64 / self.config.max_chars as f64,
+            max_tokens: self.config.max_tokens,
+            usage_ratio,
             has_summary: self.summary_message.is_some(),
         }
     }
@@ -116,28 +167,33 @@ impl ConversationContext {
     }
 
     /// Apply compaction with the given summary
-    /// Replaces old messages with summary + keeps last K turns
+    /// Replaces old messages with summary + keeps last K turns, never
+    /// cutting in the middle of an assistant turn's tool-call/tool-result unit
     pub fn apply_compaction(&mut self, summary: String) -> CompactionResult {
-        let chars_before = self.stats().total_chars;
+        let stats_before = self.stats();
+        let chars_before = stats_before.total_chars;
+        let tokens_before = stats_before.total_tokens;
         let messages_before = self.messages.len();
 
-        // Keep the last N messages (where N is keep_last_turns * 2 for user+assistant pairs)
-        let keep_count = self.config.keep_last_turns * 2;
-        let new_messages = if self.messages.len() > keep_count {
-            self.messages.split_off(self.messages.len() - keep_count)
-        } else {
-            std::mem::take(&mut self.messages)
-        };
+        let cut_index = compaction_cut_index(&self.messages, self.config.keep_last_turns);
+        let new_messages = self.messages.split_off(cut_index);
+        if !self.message_token_counts.is_empty() {
+            self.message_token_counts = self.message_token_counts.split_off(cut_index);
+        }
 
         self.messages = new_messages;
         self.summary_message = Some(summary);
 
-        let chars_after = self.stats().total_chars;
+        let stats_after = self.stats();
+        let chars_after = stats_after.total_chars;
+        let tokens_after = stats_after.total_tokens;
         let messages_after = self.messages.len();
 
         CompactionResult {
             chars_before,
             chars_after,
+            tokens_before,
+            tokens_after,
             messages_before,
             messages_after,
         }
@@ -146,26 +202,129 @@ impl ConversationContext {
     /// Clear all messages but keep system prompt and config
     pub fn clear(&mut self) {
         self.messages.clear();
+        self.message_token_counts.clear();
         self.summary_message = None;
     }
 
     /// Get the messages that should be compacted (older messages, excluding recent ones)
     pub fn messages_to_compact(&self) -> Vec<Value> {
-        let keep_count = self.config.keep_last_turns * 2;
-        if self.messages.len() <= keep_count {
-            return Vec::new();
+        let cut_index = compaction_cut_index(&self.messages, self.config.keep_last_turns);
+        self.messages[..cut_index].to_vec()
+    }
+
+    /// Persist this context to `store`: creates a new conversation row if
+    /// `id` is `None`, otherwise overwrites the existing row. Returns the
+    /// conversation id the context was saved under.
+    pub fn save(
+        &self,
+        store: &ConversationStore,
+        id: Option<ConversationId>,
+        completion_options: &Value,
+    ) -> Result<ConversationId> {
+        match id {
+            Some(id) => {
+                store.save(id, self)?;
+                Ok(id)
+            }
+            None => store.create(self, completion_options),
         }
+    }
+
+    /// Load a previously saved conversation from `store`. `config` supplies
+    /// compaction/token-counting settings, which are a runtime concern and
+    /// aren't themselves persisted.
+    pub fn load(store: &ConversationStore, id: ConversationId, config: ContextConfig) -> Result<Self> {
+        store.load(id, config)
+    }
+
+    /// Fork the conversation persisted as `id` at `at_message_index`,
+    /// starting an independent conversation that shares history up to that
+    /// point without touching the original. Returns the new conversation id.
+    pub fn fork(
+        store: &ConversationStore,
+        id: ConversationId,
+        at_message_index: usize,
+    ) -> Result<ConversationId> {
+        store.fork(id, at_message_index)
+    }
 
-        self.messages[..self.messages.len() - keep_count].to_vec()
+    /// Like `apply_compaction`, but also snapshots the messages it's about to
+    /// drop into a `compactions` row in `store` (rather than letting them
+    /// vanish — see `ConversationStore::full_transcript`) and persists the
+    /// post-compaction window back to `id`.
+    pub fn apply_compaction_persisted(
+        &mut self,
+        store: &ConversationStore,
+        id: ConversationId,
+        summary: String,
+    ) -> Result<CompactionResult> {
+        let messages_before = self.messages.len();
+        let cut_index = compaction_cut_index(&self.messages, self.config.keep_last_turns);
+        let dropped = &self.messages[..cut_index];
+        store.record_compaction(id, &summary, messages_before, dropped)?;
+        let result = self.apply_compaction(summary);
+        store.save(id, self)?;
+        Ok(result)
     }
 }
 
+/// Group `messages` into logical exchanges: each unit starts at a
+/// non-`tool`-role message and swallows every immediately trailing
+/// `role: "tool"` message, so an assistant `tool_calls` message and all of
+/// its tool results always stay together. Returns each unit's start index
+/// (half-open, ending at the next unit's start or `messages.len()`).
+fn exchange_units(messages: &[Value]) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i < messages.len() {
+        starts.push(i);
+        i += 1;
+        while i < messages.len() && message_role(&messages[i]) == "tool" {
+            i += 1;
+        }
+    }
+    starts
+}
+
+fn message_role(msg: &Value) -> &str {
+    msg["role"].as_str().unwrap_or("")
+}
+
+/// Index at which compaction should cut `messages`, keeping the last
+/// `keep_last_turns * 2` logical exchanges (user turn + assistant turn,
+/// where an assistant turn includes its trailing tool-call/tool-result
+/// messages) and never splitting a unit in half.
+fn compaction_cut_index(messages: &[Value], keep_last_turns: usize) -> usize {
+    let keep_units = keep_last_turns * 2;
+    let unit_starts = exchange_units(messages);
+    if unit_starts.len() <= keep_units {
+        return 0;
+    }
+    unit_starts[unit_starts.len() - keep_units]
+}
+
 /// Estimate character count for a message (JSON serialized)
 fn estimate_message_chars(msg: &Value) -> usize {
     // Use JSON serialization for accurate char count
     serde_json::to_string(msg).map(|s| s.len()).unwrap_or(0)
 }
 
+/// Resolve the BPE encoder for `model`, falling back to `cl100k_base` for
+/// models tiktoken-rs doesn't recognize by name (e.g. non-OpenAI models
+/// routed through an OpenAI-compatible endpoint).
+fn encoder_for_model(model: &str) -> CoreBPE {
+    tiktoken_rs::get_bpe_from_model(model)
+        .unwrap_or_else(|_| tiktoken_rs::cl100k_base().expect("cl100k_base is always available"))
+}
+
+/// Count tokens for a message the same way `estimate_message_chars` counts
+/// characters: over its JSON serialization, so token/char counts stay
+/// directly comparable.
+fn count_message_tokens(encoder: &CoreBPE, msg: &Value) -> usize {
+    let text = serde_json::to_string(msg).unwrap_or_default();
+    encoder.encode_ordinary(&text).len()
+}
+
 /// Build a prompt asking the LLM to summarize the conversation
 pub fn build_compaction_prompt(instructions: Option<&str>) -> String {
     let base = r#"Summarize this conversation for context continuation. Include:
@@ -173,8 +332,9 @@ pub fn build_compaction_prompt(instructions: Option<&str>) -> String {
 2. File changes performed (paths and what was changed)
 3. Outstanding TODOs or next steps
 4. Important commands run and their outcomes
-5. Any errors encountered and how they were resolved
-6. User preferences discovered
+5. Tool calls made (tool name and arguments) and their outcomes, especially any that changed state
+6. Any errors encountered and how they were resolved
+7. User preferences discovered
 
 Be concise but comprehensive. The summary will replace older messages to save context space."#;
 
@@ -297,6 +457,91 @@ mod tests {
         assert!(ctx.needs_compaction());
     }
 
+    #[test]
+    fn test_token_count_mode() {
+        let mut config = default_config();
+        config.count_mode = CountMode::Tokens;
+        config.max_tokens = 1_000_000;
+
+        let mut ctx = ConversationContext::new("You are helpful.".to_string(), config);
+        ctx.push_message(json!({"role": "user", "content": "Hello world"}));
+
+        let stats = ctx.stats();
+        assert!(stats.total_tokens > 0);
+        // Char counters still populate in token mode so callers can compare both
+        assert!(stats.total_chars > 0);
+        assert!(!ctx.needs_compaction());
+    }
+
+    #[test]
+    fn test_apply_compaction_keeps_tool_call_units_intact() {
+        let mut config = default_config();
+        config.keep_last_turns = 1; // keep last 2 units: user turn + assistant turn
+
+        let mut ctx = ConversationContext::new("System".to_string(), config);
+
+        // Older turn
+        ctx.push_message(json!({"role": "user", "content": "List files"}));
+        ctx.push_message(json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{"id": "call_1", "type": "function", "function": {"name": "Glob", "arguments": "{}"}}]
+        }));
+        ctx.push_message(json!({"role": "tool", "tool_call_id": "call_1", "content": "a.rs\nb.rs"}));
+
+        // Most recent turn, also with a tool call
+        ctx.push_message(json!({"role": "user", "content": "Read a.rs"}));
+        ctx.push_message(json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{"id": "call_2", "type": "function", "function": {"name": "Read", "arguments": "{}"}}]
+        }));
+        ctx.push_message(json!({"role": "tool", "tool_call_id": "call_2", "content": "fn main() {}"}));
+
+        let result = ctx.apply_compaction("Summary of older turn".to_string());
+
+        assert_eq!(result.messages_before, 6);
+        // The kept window is exactly the last user+assistant-with-tool-result unit pair
+        assert_eq!(ctx.messages.len(), 3);
+        assert_eq!(ctx.messages[0]["content"], "Read a.rs");
+        assert_eq!(ctx.messages[1]["role"], "assistant");
+        assert_eq!(ctx.messages[2]["role"], "tool");
+        assert_eq!(ctx.messages[2]["tool_call_id"], "call_2");
+    }
+
+    #[test]
+    fn test_apply_compaction_keeps_multi_result_tool_unit_intact() {
+        let mut config = default_config();
+        config.keep_last_turns = 1;
+
+        let mut ctx = ConversationContext::new("System".to_string(), config);
+
+        ctx.push_message(json!({"role": "user", "content": "old turn"}));
+        ctx.push_message(json!({"role": "assistant", "content": "ack"}));
+
+        // Most recent turn: one assistant message requests two tools, both
+        // results must stay attached to it after compaction.
+        ctx.push_message(json!({"role": "user", "content": "Do two things"}));
+        ctx.push_message(json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [
+                {"id": "call_a", "type": "function", "function": {"name": "Glob", "arguments": "{}"}},
+                {"id": "call_b", "type": "function", "function": {"name": "Read", "arguments": "{}"}}
+            ]
+        }));
+        ctx.push_message(json!({"role": "tool", "tool_call_id": "call_a", "content": "result a"}));
+        ctx.push_message(json!({"role": "tool", "tool_call_id": "call_b", "content": "result b"}));
+
+        ctx.apply_compaction("Summary".to_string());
+
+        assert_eq!(ctx.messages.len(), 4);
+        assert_eq!(ctx.messages[0]["content"], "Do two things");
+        assert_eq!(ctx.messages[1]["role"], "assistant");
+        assert_eq!(ctx.messages[2]["tool_call_id"], "call_a");
+        assert_eq!(ctx.messages[3]["tool_call_id"], "call_b");
+    }
+
     #[test]
     fn test_needs_compaction_disabled() {
         let mut config = default_config();
@@ -0,0 +1,294 @@
+//! SQLite-backed persistence for `ConversationContext`.
+//!
+//! Conversations are forkable: `fork` snapshots a conversation's messages up
+//! to a given exchange and starts a new, independent conversation row
+//! pointing back at its parent, so retrying from an earlier point doesn't
+//! lose the original thread. Compaction writes the superseded messages
+//! themselves (not just a count) into `compactions` alongside the summary,
+//! so `full_transcript` can reconstruct the pre-compaction history even
+//! though `messages`/`load` only ever hold the live, compacted window.
+
+use crate::config::ContextConfig;
+use crate::context::ConversationContext;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Schema version stamped on every conversation row, so a future migration
+/// can tell which rows it needs to touch.
+const SCHEMA_VERSION: i64 = 1;
+
+/// A persisted conversation's identity within the store.
+pub type ConversationId = i64;
+
+pub struct ConversationStore {
+    conn: Connection,
+}
+
+const SCHEMA_SQL: &str = r#"
+    CREATE TABLE IF NOT EXISTS conversations (
+        id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+        parent_conversation_id  INTEGER,
+        fork_exchange_id        INTEGER,
+        system_prompt           TEXT NOT NULL,
+        summary                 TEXT,
+        completion_options      TEXT NOT NULL,
+        schema_version          INTEGER NOT NULL,
+        created_at              INTEGER NOT NULL,
+        updated_at              INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS messages (
+        conversation_id INTEGER NOT NULL,
+        ordinal         INTEGER NOT NULL,
+        content         TEXT NOT NULL,
+        PRIMARY KEY (conversation_id, ordinal)
+    );
+    CREATE TABLE IF NOT EXISTS compactions (
+        conversation_id  INTEGER NOT NULL,
+        messages_before  INTEGER NOT NULL,
+        summary          TEXT NOT NULL,
+        dropped_messages TEXT NOT NULL,
+        created_at       INTEGER NOT NULL
+    );
+"#;
+
+impl ConversationStore {
+    /// Open (creating if needed) the SQLite database at `path` and ensure
+    /// its schema exists.
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path).context("opening conversation store")?;
+        conn.execute_batch(SCHEMA_SQL)?;
+        Ok(Self { conn })
+    }
+
+    /// Open an in-memory store with the same schema as `open`; used by tests
+    /// that don't need to persist across process restarts.
+    #[cfg(test)]
+    fn open_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory().context("opening in-memory conversation store")?;
+        conn.execute_batch(SCHEMA_SQL)?;
+        Ok(Self { conn })
+    }
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64
+    }
+
+    /// Insert a new, parentless conversation row for `ctx` and persist its
+    /// current messages. Returns the new conversation id.
+    pub fn create(
+        &self,
+        ctx: &ConversationContext,
+        completion_options: &Value,
+    ) -> Result<ConversationId> {
+        let now = Self::now();
+        self.conn.execute(
+            "INSERT INTO conversations
+                (parent_conversation_id, fork_exchange_id, system_prompt, summary, completion_options, schema_version, created_at, updated_at)
+             VALUES (NULL, NULL, ?1, ?2, ?3, ?4, ?5, ?5)",
+            params![
+                ctx.system_prompt,
+                ctx.summary_message,
+                serde_json::to_string(completion_options)?,
+                SCHEMA_VERSION,
+                now,
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.replace_messages(id, &ctx.messages)?;
+        Ok(id)
+    }
+
+    /// Persist `ctx`'s current system prompt/summary/messages over an
+    /// existing conversation row.
+    pub fn save(&self, id: ConversationId, ctx: &ConversationContext) -> Result<()> {
+        self.conn.execute(
+            "UPDATE conversations SET system_prompt = ?1, summary = ?2, updated_at = ?3 WHERE id = ?4",
+            params![ctx.system_prompt, ctx.summary_message, Self::now(), id],
+        )?;
+        self.replace_messages(id, &ctx.messages)?;
+        Ok(())
+    }
+
+    fn replace_messages(&self, conversation_id: ConversationId, messages: &[Value]) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1",
+            params![conversation_id],
+        )?;
+        for (ordinal, message) in messages.iter().enumerate() {
+            self.conn.execute(
+                "INSERT INTO messages (conversation_id, ordinal, content) VALUES (?1, ?2, ?3)",
+                params![
+                    conversation_id,
+                    ordinal as i64,
+                    serde_json::to_string(message)?
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Load a conversation's system prompt/summary/messages into a fresh
+    /// `ConversationContext`. `config` supplies compaction/token-counting
+    /// settings, which are a runtime concern and aren't themselves persisted.
+    pub fn load(&self, id: ConversationId, config: ContextConfig) -> Result<ConversationContext> {
+        let (system_prompt, summary): (String, Option<String>) = self
+            .conn
+            .query_row(
+                "SELECT system_prompt, summary FROM conversations WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .context("loading conversation row")?;
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT content FROM messages WHERE conversation_id = ?1 ORDER BY ordinal")?;
+        let messages = stmt
+            .query_map(params![id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        let mut ctx = ConversationContext::new(system_prompt, config);
+        ctx.summary_message = summary;
+        for message in messages {
+            ctx.push_message(serde_json::from_str(&message)?);
+        }
+        Ok(ctx)
+    }
+
+    /// Fork `id` at `at_message_index`: starts a new conversation row that
+    /// carries the parent's system prompt/summary (as of the fork) and
+    /// `messages[..at_message_index]`, pointing back at `id` and
+    /// `at_message_index` via `parent_conversation_id`/`fork_exchange_id`.
+    /// The original conversation is untouched. Returns the new id.
+    pub fn fork(&self, id: ConversationId, at_message_index: usize) -> Result<ConversationId> {
+        let (system_prompt, summary, completion_options): (String, Option<String>, String) = self
+            .conn
+            .query_row(
+                "SELECT system_prompt, summary, completion_options FROM conversations WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .context("loading parent conversation row")?;
+
+        let now = Self::now();
+        self.conn.execute(
+            "INSERT INTO conversations
+                (parent_conversation_id, fork_exchange_id, system_prompt, summary, completion_options, schema_version, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            params![
+                id,
+                at_message_index as i64,
+                system_prompt,
+                summary,
+                completion_options,
+                SCHEMA_VERSION,
+                now,
+            ],
+        )?;
+        let new_id = self.conn.last_insert_rowid();
+
+        self.conn.execute(
+            "INSERT INTO messages (conversation_id, ordinal, content)
+             SELECT ?1, ordinal, content FROM messages WHERE conversation_id = ?2 AND ordinal < ?3",
+            params![new_id, id, at_message_index as i64],
+        )?;
+
+        Ok(new_id)
+    }
+
+    /// Record a compaction as a row in `compactions`, snapshotting the
+    /// `dropped` messages it superseded (not just their count) alongside the
+    /// summary, so `full_transcript` can recover them even after `messages`
+    /// is overwritten with the post-compaction window.
+    pub fn record_compaction(
+        &self,
+        id: ConversationId,
+        summary: &str,
+        messages_before: usize,
+        dropped: &[Value],
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO compactions (conversation_id, messages_before, summary, dropped_messages, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                id,
+                messages_before as i64,
+                summary,
+                serde_json::to_string(dropped)?,
+                Self::now(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Reconstruct the full pre-compaction transcript for `id`: every
+    /// compaction's `dropped_messages`, oldest first, followed by the
+    /// still-live `messages` row. Unlike `load`, this never hands back a
+    /// `ConversationContext` for active use — it exists purely so a
+    /// compacted conversation's original history can still be inspected.
+    pub fn full_transcript(&self, id: ConversationId) -> Result<Vec<Value>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT dropped_messages FROM compactions WHERE conversation_id = ?1 ORDER BY created_at ASC",
+        )?;
+        let compaction_batches = stmt
+            .query_map(params![id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        let mut transcript = Vec::new();
+        for batch in compaction_batches {
+            let dropped: Vec<Value> = serde_json::from_str(&batch)?;
+            transcript.extend(dropped);
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT content FROM messages WHERE conversation_id = ?1 ORDER BY ordinal")?;
+        let live_messages = stmt
+            .query_map(params![id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        for message in live_messages {
+            transcript.push(serde_json::from_str(&message)?);
+        }
+
+        Ok(transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ContextConfig;
+    use serde_json::json;
+
+    #[test]
+    fn test_full_transcript_survives_compaction() {
+        let store = ConversationStore::open_in_memory().unwrap();
+        let mut config = ContextConfig::default();
+        config.keep_last_turns = 2; // keep last 4 messages
+
+        let mut ctx = ConversationContext::new("System".to_string(), config.clone());
+        for i in 0..10 {
+            ctx.push_message(json!({"role": "user", "content": format!("message {}", i)}));
+        }
+
+        let id = ctx.save(&store, None, &json!({})).unwrap();
+
+        ctx.apply_compaction_persisted(&store, id, "Summary of early messages".to_string())
+            .unwrap();
+
+        // The live window no longer has the dropped messages...
+        let reloaded = store.load(id, config).unwrap();
+        assert_eq!(reloaded.messages.len(), 4);
+
+        // ...but they're still recoverable from the full transcript.
+        let transcript = store.full_transcript(id).unwrap();
+        assert_eq!(transcript.len(), 10);
+        assert_eq!(transcript[0]["content"], "message 0");
+        assert_eq!(transcript[9]["content"], "message 9");
+    }
+}
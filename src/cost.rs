@@ -0,0 +1,27 @@
+//! Per-model pricing and cost computation from token usage.
+
+use crate::llm::Usage;
+use serde::{Deserialize, Serialize};
+
+/// USD-per-1M-token pricing for a single model.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPricing {
+    pub fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// Compute the USD cost of a completed call from its reported token usage.
+    pub fn cost_usd(&self, usage: &Usage) -> f64 {
+        let input_cost = usage.prompt_tokens as f64 / 1_000_000.0 * self.input_per_million;
+        let output_cost = usage.completion_tokens as f64 / 1_000_000.0 * self.output_per_million;
+        input_cost + output_cost
+    }
+}
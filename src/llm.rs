@@ -7,7 +7,10 @@ use anyhow::{anyhow, Result};
 use rand::Rng;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
@@ -17,6 +20,12 @@ const INITIAL_BACKOFF_MS: u64 = 1000; // 1 second
 const MAX_BACKOFF_MS: u64 = 60000; // 60 seconds
 const JITTER_FACTOR: f64 = 0.3; // ±30% jitter
 
+/// API version header required by Anthropic's Messages API
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// `ChatRequest` has no per-request token budget field, so `AnthropicProvider`
+/// (whose API requires `max_tokens`) falls back to this default.
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 4096;
+
 /// Check if an HTTP status code is retryable (429 rate limit or 5xx server error)
 fn is_retryable_status(code: u16) -> bool {
     code == 429 || (500..600).contains(&code)
@@ -30,7 +39,7 @@ fn jittered_backoff(base_ms: u64) -> u64 {
     (jittered as u64).min(MAX_BACKOFF_MS)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ChatRequest {
     pub model: String,
     pub messages: Vec<Value>,
@@ -38,6 +47,14 @@ pub struct ChatRequest {
     pub tools: Option<Vec<Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<String>,
+    /// Requests `text/event-stream` incremental output; set by `chat_stream`
+    /// itself, callers of `chat`/`chat_with_metadata` don't need to touch it
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub stream: bool,
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
 }
 
 /// Token usage statistics from the API response
@@ -93,6 +110,180 @@ pub struct LlmCallResult {
     pub retries: u32,
 }
 
+/// An incremental fragment of a streamed chat completion, as delivered to
+/// the `on_delta` callback of `chat_stream`
+#[derive(Debug, Clone, Default)]
+pub struct StreamDelta {
+    pub content: Option<String>,
+    pub tool_calls: Vec<ToolCallDelta>,
+    pub finish_reason: Option<String>,
+}
+
+/// One tool call's incremental fragment. `arguments` arrives split across
+/// multiple deltas and must be concatenated by `index` to reconstruct the
+/// full JSON arguments string.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolCallDelta {
+    pub index: usize,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(rename = "type", default)]
+    pub call_type: Option<String>,
+    #[serde(default)]
+    pub function: Option<FunctionCallDelta>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FunctionCallDelta {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChunkWire {
+    #[serde(default)]
+    choices: Vec<StreamChoiceWire>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StreamChoiceWire {
+    #[serde(default)]
+    delta: StreamDeltaWire,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StreamDeltaWire {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCallDelta>,
+}
+
+/// Accumulates streamed deltas into a final buffered `ChatResponse`,
+/// concatenating `content` fragments and merging `tool_calls` by index
+/// (OpenAI-style streams split each tool call's `arguments` across
+/// multiple deltas).
+#[derive(Default)]
+struct StreamAccumulator {
+    content: String,
+    has_content: bool,
+    tool_calls: BTreeMap<usize, ToolCallBuilder>,
+    finish_reason: Option<String>,
+}
+
+#[derive(Default)]
+struct ToolCallBuilder {
+    id: String,
+    call_type: String,
+    name: String,
+    arguments: String,
+}
+
+impl StreamAccumulator {
+    fn apply(&mut self, delta: &StreamDelta) {
+        if let Some(content) = &delta.content {
+            self.content.push_str(content);
+            self.has_content = true;
+        }
+
+        for tool_call in &delta.tool_calls {
+            let builder = self.tool_calls.entry(tool_call.index).or_default();
+            if let Some(id) = &tool_call.id {
+                builder.id = id.clone();
+            }
+            if let Some(call_type) = &tool_call.call_type {
+                builder.call_type = call_type.clone();
+            }
+            if let Some(function) = &tool_call.function {
+                if let Some(name) = &function.name {
+                    builder.name.push_str(name);
+                }
+                if let Some(arguments) = &function.arguments {
+                    builder.arguments.push_str(arguments);
+                }
+            }
+        }
+
+        if delta.finish_reason.is_some() {
+            self.finish_reason = delta.finish_reason.clone();
+        }
+    }
+
+    fn into_response(self) -> ChatResponse {
+        let tool_calls: Vec<ToolCall> = self
+            .tool_calls
+            .into_values()
+            .map(|builder| ToolCall {
+                id: builder.id,
+                call_type: if builder.call_type.is_empty() {
+                    "function".to_string()
+                } else {
+                    builder.call_type
+                },
+                function: FunctionCall {
+                    name: builder.name,
+                    arguments: builder.arguments,
+                },
+            })
+            .collect();
+
+        let message = Message {
+            role: "assistant".to_string(),
+            content: self.has_content.then_some(self.content),
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        };
+
+        ChatResponse {
+            choices: vec![Choice {
+                message,
+                finish_reason: self.finish_reason,
+            }],
+            usage: None,
+        }
+    }
+}
+
+/// Read a `text/event-stream` response body line by line, stripping the
+/// `data: ` prefix and ignoring the terminal `data: [DONE]` marker, invoking
+/// `on_delta` per chunk and accumulating into a final `ChatResponse`.
+fn read_stream(
+    response: reqwest::blocking::Response,
+    on_delta: &mut impl FnMut(StreamDelta),
+) -> Result<ChatResponse> {
+    let reader = std::io::BufReader::new(response);
+    let mut accumulator = StreamAccumulator::default();
+
+    for line in reader.lines() {
+        let line = line?;
+        let Some(payload) = line.strip_prefix("data: ") else {
+            continue;
+        };
+        if payload.is_empty() || payload == "[DONE]" {
+            continue;
+        }
+
+        let chunk: StreamChunkWire = serde_json::from_str(payload)?;
+        let Some(choice) = chunk.choices.into_iter().next() else {
+            continue;
+        };
+
+        let delta = StreamDelta {
+            content: choice.delta.content,
+            tool_calls: choice.delta.tool_calls,
+            finish_reason: choice.finish_reason,
+        };
+
+        accumulator.apply(&delta);
+        on_delta(delta);
+    }
+
+    Ok(accumulator.into_response())
+}
+
 /// Trait for LLM clients to allow mocking and abstraction
 pub trait LlmClient {
     /// Synchronous chat call (may internally use async)
@@ -110,12 +301,393 @@ pub trait LlmClient {
     }
 }
 
+/// Encodes/decodes the wire format for a specific chat completion API, so
+/// the same internal `ChatRequest`/retry loop in `Client` can drive
+/// multiple backends instead of hardcoding the OpenAI-style shape.
+pub trait Provider: Send + Sync {
+    /// Short identifier used in error messages, e.g. "openai"
+    fn name(&self) -> &'static str;
+
+    /// Full URL to POST a chat completion request to
+    fn endpoint(&self, base_url: &str) -> String;
+
+    /// Headers carrying the API key, e.g. `Authorization: Bearer ...` or
+    /// `x-api-key: ...`
+    fn auth_headers(&self, api_key: &str) -> Vec<(String, String)>;
+
+    /// Translate our internal `ChatRequest` into this API's JSON body
+    fn encode_request(&self, request: &ChatRequest) -> Value;
+
+    /// Translate this API's JSON response into our internal `ChatResponse`
+    fn decode_response(&self, body: Value) -> Result<ChatResponse>;
+
+    /// Whether this provider natively understands `tools`/`tool_choice`.
+    /// `false` for providers backing weaker models that only follow
+    /// plain-text instructions; `Client` then either errors with
+    /// `LlmError::FunctionCallingUnsupported` or falls back to prompt-based
+    /// tool emulation, depending on `Client::with_prompt_tool_fallback`.
+    fn supports_tools(&self) -> bool {
+        true
+    }
+}
+
+/// Default provider: the OpenAI-compatible `/chat/completions` endpoint this
+/// client originally hardcoded
+#[derive(Debug, Clone, Default)]
+pub struct OpenAiProvider;
+
+impl Provider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn endpoint(&self, base_url: &str) -> String {
+        format!("{}/chat/completions", base_url)
+    }
+
+    fn auth_headers(&self, api_key: &str) -> Vec<(String, String)> {
+        vec![("Authorization".to_string(), format!("Bearer {}", api_key))]
+    }
+
+    fn encode_request(&self, request: &ChatRequest) -> Value {
+        serde_json::to_value(request).expect("ChatRequest always serializes")
+    }
+
+    fn decode_response(&self, body: Value) -> Result<ChatResponse> {
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+/// Append `block` to `messages` as a `"user"` turn, merging into the previous
+/// message instead of pushing a new one if that message is already a `"user"`
+/// turn. Anthropic's Messages API requires roles to alternate and rejects
+/// consecutive same-role turns with a 400; a parallel tool call produces
+/// several consecutive `role: "tool"` messages (one per call), and a `tool`
+/// result can also be directly followed by a plain user turn, both of which
+/// would otherwise encode as back-to-back `"user"` messages.
+fn push_or_merge_user_turn(messages: &mut Vec<Value>, block: Value) {
+    if let Some(last) = messages.last_mut() {
+        if last.get("role").and_then(|r| r.as_str()) == Some("user") {
+            if let Some(content) = last.get_mut("content").and_then(|c| c.as_array_mut()) {
+                content.push(block);
+                return;
+            }
+        }
+    }
+    messages.push(json!({ "role": "user", "content": [block] }));
+}
+
+/// Maps our internal `ChatRequest`/`ChatResponse` onto Anthropic's Messages
+/// API: hoists the `system` message out of `messages`, translates OpenAI-style
+/// tool schemas into `input_schema`, and converts `tool_use`/`tool_result`
+/// content blocks back into `ToolCall`/`Message`.
+#[derive(Debug, Clone, Default)]
+pub struct AnthropicProvider;
+
+impl Provider for AnthropicProvider {
+    fn name(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn endpoint(&self, base_url: &str) -> String {
+        format!("{}/v1/messages", base_url)
+    }
+
+    fn auth_headers(&self, api_key: &str) -> Vec<(String, String)> {
+        vec![
+            ("x-api-key".to_string(), api_key.to_string()),
+            (
+                "anthropic-version".to_string(),
+                ANTHROPIC_VERSION.to_string(),
+            ),
+        ]
+    }
+
+    fn encode_request(&self, request: &ChatRequest) -> Value {
+        let mut system = String::new();
+        let mut messages = Vec::new();
+
+        for message in &request.messages {
+            let role = message
+                .get("role")
+                .and_then(|r| r.as_str())
+                .unwrap_or("user");
+
+            if role == "system" {
+                if let Some(content) = message.get("content").and_then(|c| c.as_str()) {
+                    if !system.is_empty() {
+                        system.push('\n');
+                    }
+                    system.push_str(content);
+                }
+                continue;
+            }
+
+            if role == "tool" {
+                let tool_use_id = message
+                    .get("tool_call_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                let content = message
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                let block = json!({
+                    "type": "tool_result",
+                    "tool_use_id": tool_use_id,
+                    "content": content,
+                });
+                push_or_merge_user_turn(&mut messages, block);
+                continue;
+            }
+
+            let mut content_blocks = Vec::new();
+            if let Some(text) = message.get("content").and_then(|c| c.as_str()) {
+                if !text.is_empty() {
+                    content_blocks.push(json!({ "type": "text", "text": text }));
+                }
+            }
+            if let Some(tool_calls) = message.get("tool_calls").and_then(|v| v.as_array()) {
+                for tool_call in tool_calls {
+                    let id = tool_call.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+                    let function = tool_call.get("function");
+                    let name = function
+                        .and_then(|f| f.get("name"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    let input = function
+                        .and_then(|f| f.get("arguments"))
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| serde_json::from_str::<Value>(s).ok())
+                        .unwrap_or_else(|| json!({}));
+                    content_blocks.push(json!({
+                        "type": "tool_use",
+                        "id": id,
+                        "name": name,
+                        "input": input,
+                    }));
+                }
+            }
+
+            if role == "user" {
+                // A user turn may directly follow coalesced tool_result blocks
+                // (see push_or_merge_user_turn); merge into that same turn
+                // rather than emitting a second consecutive "user" message.
+                for block in content_blocks {
+                    push_or_merge_user_turn(&mut messages, block);
+                }
+            } else {
+                messages.push(json!({ "role": role, "content": content_blocks }));
+            }
+        }
+
+        let mut body = json!({
+            "model": request.model,
+            "max_tokens": ANTHROPIC_DEFAULT_MAX_TOKENS,
+            "messages": messages,
+        });
+
+        if !system.is_empty() {
+            body["system"] = Value::String(system);
+        }
+
+        if let Some(tools) = &request.tools {
+            let anthropic_tools: Vec<Value> = tools
+                .iter()
+                .filter_map(|tool| {
+                    let function = tool.get("function")?;
+                    Some(json!({
+                        "name": function.get("name")?.as_str()?,
+                        "description": function
+                            .get("description")
+                            .and_then(|d| d.as_str())
+                            .unwrap_or(""),
+                        "input_schema": function
+                            .get("parameters")
+                            .cloned()
+                            .unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+                    }))
+                })
+                .collect();
+            body["tools"] = Value::Array(anthropic_tools);
+        }
+
+        // Our `tool_choice` is a plain string (e.g. "auto"/"required"), so only
+        // the cases Anthropic has a direct equivalent for are translated;
+        // anything else (including "none") is left for Anthropic's default.
+        if let Some(tool_choice) = &request.tool_choice {
+            let mapped = match tool_choice.as_str() {
+                "auto" => Some(json!({ "type": "auto" })),
+                "required" => Some(json!({ "type": "any" })),
+                _ => None,
+            };
+            if let Some(mapped) = mapped {
+                body["tool_choice"] = mapped;
+            }
+        }
+
+        body
+    }
+
+    fn decode_response(&self, body: Value) -> Result<ChatResponse> {
+        let content_blocks = body
+            .get("content")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+
+        for block in &content_blocks {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => {
+                    if let Some(chunk) = block.get("text").and_then(|t| t.as_str()) {
+                        text.push_str(chunk);
+                    }
+                }
+                Some("tool_use") => {
+                    let id = block
+                        .get("id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string();
+                    let name = block
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string();
+                    let arguments = block
+                        .get("input")
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "{}".to_string());
+                    tool_calls.push(ToolCall {
+                        id,
+                        call_type: "function".to_string(),
+                        function: FunctionCall { name, arguments },
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let finish_reason = body
+            .get("stop_reason")
+            .and_then(|v| v.as_str())
+            .map(|reason| match reason {
+                "end_turn" | "stop_sequence" => "stop".to_string(),
+                "max_tokens" => "length".to_string(),
+                "tool_use" => "tool_calls".to_string(),
+                other => other.to_string(),
+            });
+
+        let usage = body.get("usage").map(|u| Usage {
+            prompt_tokens: u.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+            completion_tokens: u.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+        });
+
+        let message = Message {
+            role: "assistant".to_string(),
+            content: (!text.is_empty()).then_some(text),
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        };
+
+        Ok(ChatResponse {
+            choices: vec![Choice {
+                message,
+                finish_reason,
+            }],
+            usage,
+        })
+    }
+}
+
+/// Typed errors for `Client`, distinct from the generic `anyhow!` wrapping
+/// used for opaque API/connection failures
+#[derive(Debug)]
+pub enum LlmError {
+    /// `self.provider` doesn't support native function calling and
+    /// `with_prompt_tool_fallback` wasn't enabled to emulate it
+    FunctionCallingUnsupported { provider: &'static str },
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FunctionCallingUnsupported { provider } => write!(
+                f,
+                "provider '{}' does not support function calling",
+                provider
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+/// Instructs the model to reply with a fenced JSON tool call instead of
+/// using native function calling, for providers/models where
+/// `Provider::supports_tools` is `false`.
+fn tool_catalog_prompt(tools: &[Value]) -> String {
+    let mut prompt = String::from(
+        "You have access to the following tools, but this model can't call them \
+         natively. To use one, reply with *only* a fenced JSON block of the form:\n\n\
+         ```json\n{\"tool\": \"<name>\", \"arguments\": { ... }}\n```\n\nTool catalog:\n",
+    );
+
+    for tool in tools {
+        let Some(function) = tool.get("function") else {
+            continue;
+        };
+        let name = function.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let description = function
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        prompt.push_str(&format!("- {name}: {description}\n"));
+        if let Some(parameters) = function.get("parameters") {
+            prompt.push_str(&format!("  parameters: {parameters}\n"));
+        }
+    }
+
+    prompt
+}
+
+/// Parse a prompt-emulated tool call out of the model's fenced-JSON reply
+/// (see `tool_catalog_prompt`), if present
+fn parse_prompt_tool_call(content: &str) -> Option<ToolCall> {
+    let fence_start = content.find("```")?;
+    let after_open = &content[fence_start + 3..];
+    let body_start = after_open.find('\n').map(|i| i + 1).unwrap_or(0);
+    let body = &after_open[body_start..];
+    let fence_end = body.find("```")?;
+    let json_text = body[..fence_end].trim();
+
+    let parsed: Value = serde_json::from_str(json_text).ok()?;
+    let name = parsed.get("tool")?.as_str()?.to_string();
+    let arguments = parsed.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+    Some(ToolCall {
+        id: format!("prompt-call-{name}"),
+        call_type: "function".to_string(),
+        function: FunctionCall {
+            name,
+            arguments: arguments.to_string(),
+        },
+    })
+}
+
 pub struct Client {
     base_url: String,
     /// API key wrapped in SecretString for secure memory handling.
     /// Will be zeroized on drop and won't leak via Debug/Display.
     api_key: SecretString,
     http_client: reqwest::blocking::Client,
+    provider: Arc<dyn Provider>,
+    /// When `true` and `provider.supports_tools()` is `false`, emulate tool
+    /// calling via `tool_catalog_prompt`/`parse_prompt_tool_call` instead of
+    /// returning `LlmError::FunctionCallingUnsupported`
+    prompt_tool_fallback: bool,
 }
 
 impl Client {
@@ -132,13 +704,38 @@ impl Client {
             base_url: base_url.trim_end_matches('/').to_string(),
             api_key,
             http_client,
+            provider: Arc::new(OpenAiProvider),
+            prompt_tool_fallback: false,
         }
     }
 
-    /// Internal sync implementation with retry logic
-    fn chat_sync(&self, request: &ChatRequest) -> Result<LlmCallResult> {
-        let url = format!("{}/chat/completions", self.base_url);
-        let start = std::time::Instant::now();
+    /// Use a different wire format (e.g. `AnthropicProvider`) instead of the
+    /// default OpenAI-compatible one
+    pub fn with_provider(mut self, provider: Arc<dyn Provider>) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// When `self.provider.supports_tools()` is `false`, emulate tool
+    /// calling through the system prompt (see `tool_catalog_prompt`) instead
+    /// of returning `LlmError::FunctionCallingUnsupported`. Off by default.
+    pub fn with_prompt_tool_fallback(mut self, enabled: bool) -> Self {
+        self.prompt_tool_fallback = enabled;
+        self
+    }
+
+    /// POST `request`, encoded by `self.provider`, with retry/backoff on
+    /// connection errors and retryable HTTP statuses, returning the first
+    /// successful response (uninspected) along with how many retries it
+    /// took. Shared by `chat_sync` and `chat_stream` so both get identical
+    /// retry behavior; `chat_stream` only calls this before any byte of the
+    /// response body has been read, since a partially-streamed response
+    /// can't be safely retried without double-delivering deltas already
+    /// handed to the caller's callback.
+    fn send_with_retry(&self, request: &ChatRequest) -> Result<(reqwest::blocking::Response, u32)> {
+        let url = self.provider.endpoint(&self.base_url);
+        let headers = self.provider.auth_headers(self.api_key.expose_secret());
+        let body = self.provider.encode_request(request);
 
         let mut attempt = 0;
         let mut backoff_ms = INITIAL_BACKOFF_MS;
@@ -147,15 +744,14 @@ impl Client {
         loop {
             attempt += 1;
 
-            let resp = self
-                .http_client
-                .post(&url)
-                .header(
-                    "Authorization",
-                    format!("Bearer {}", self.api_key.expose_secret()),
-                )
+            let mut req_builder = self.http_client.post(&url);
+            for (name, value) in &headers {
+                req_builder = req_builder.header(name, value);
+            }
+
+            let resp = req_builder
                 .header("Content-Type", "application/json")
-                .json(request)
+                .json(&body)
                 .send();
 
             match resp {
@@ -163,12 +759,7 @@ impl Client {
                     let status = response.status();
 
                     if status.is_success() {
-                        let body: ChatResponse = response.json()?;
-                        return Ok(LlmCallResult {
-                            response: body,
-                            latency_ms: start.elapsed().as_millis() as u64,
-                            retries: total_retries,
-                        });
+                        return Ok((response, total_retries));
                     }
 
                     let code = status.as_u16();
@@ -230,6 +821,95 @@ impl Client {
             }
         }
     }
+
+    /// Internal sync implementation with retry logic
+    fn chat_sync(&self, request: &ChatRequest) -> Result<LlmCallResult> {
+        let start = std::time::Instant::now();
+
+        if request.tools.is_some() && !self.provider.supports_tools() {
+            if !self.prompt_tool_fallback {
+                return Err(LlmError::FunctionCallingUnsupported {
+                    provider: self.provider.name(),
+                }
+                .into());
+            }
+            return self.chat_sync_with_prompt_tools(request, start);
+        }
+
+        let (response, total_retries) = self.send_with_retry(request)?;
+        let body: Value = response.json()?;
+        let chat_response = self.provider.decode_response(body)?;
+
+        Ok(LlmCallResult {
+            response: chat_response,
+            latency_ms: start.elapsed().as_millis() as u64,
+            retries: total_retries,
+        })
+    }
+
+    /// Emulate tool calling for a provider with `supports_tools() == false`:
+    /// strip `tools`/`tool_choice` from the outgoing request, prepend a
+    /// system message listing the tool catalog, then parse the model's
+    /// fenced-JSON reply back into a `ToolCall` so the multi-step tool loop
+    /// (see `persona::tool_loop`) still works uniformly.
+    fn chat_sync_with_prompt_tools(
+        &self,
+        request: &ChatRequest,
+        start: std::time::Instant,
+    ) -> Result<LlmCallResult> {
+        let mut fallback_request = request.clone();
+        let tools = fallback_request.tools.take();
+        fallback_request.tool_choice = None;
+
+        if let Some(tools) = &tools {
+            let catalog = tool_catalog_prompt(tools);
+            fallback_request
+                .messages
+                .insert(0, json!({ "role": "system", "content": catalog }));
+        }
+
+        let (response, total_retries) = self.send_with_retry(&fallback_request)?;
+        let body: Value = response.json()?;
+        let mut chat_response = self.provider.decode_response(body)?;
+
+        if let Some(choice) = chat_response.choices.first_mut() {
+            let tool_call = choice
+                .message
+                .content
+                .as_deref()
+                .and_then(parse_prompt_tool_call);
+            if let Some(tool_call) = tool_call {
+                choice.message.tool_calls = Some(vec![tool_call]);
+                choice.finish_reason = Some("tool_calls".to_string());
+            }
+        }
+
+        Ok(LlmCallResult {
+            response: chat_response,
+            latency_ms: start.elapsed().as_millis() as u64,
+            retries: total_retries,
+        })
+    }
+
+    /// Stream a chat completion as `text/event-stream`, invoking `on_delta`
+    /// for each incremental chunk and returning the fully accumulated
+    /// response once the stream ends. Lets the TUI show tokens as they
+    /// arrive instead of waiting for the full buffered response.
+    ///
+    /// The delta format read here is OpenAI's `choices[0].delta` shape, so
+    /// this only streams correctly against `OpenAiProvider` today; other
+    /// providers still work through `chat`/`chat_with_metadata`.
+    pub fn chat_stream(
+        &self,
+        request: &ChatRequest,
+        mut on_delta: impl FnMut(StreamDelta),
+    ) -> Result<ChatResponse> {
+        let mut stream_request = request.clone();
+        stream_request.stream = true;
+
+        let (response, _retries) = self.send_with_retry(&stream_request)?;
+        read_stream(response, &mut on_delta)
+    }
 }
 
 impl LlmClient for Client {
@@ -268,4 +948,42 @@ mod tests {
         assert!(!is_retryable_status(401));
         assert!(!is_retryable_status(404));
     }
+
+    #[test]
+    fn test_anthropic_encode_request_coalesces_parallel_tool_results() {
+        // An assistant turn that requested two tools in parallel produces two
+        // consecutive `role: "tool"` messages; Anthropic requires alternating
+        // roles, so both results must land in a single "user" message.
+        let request = ChatRequest {
+            model: "claude-3-5-sonnet".to_string(),
+            messages: vec![
+                json!({"role": "user", "content": "what's the weather in two cities?"}),
+                json!({
+                    "role": "assistant",
+                    "tool_calls": [
+                        {"id": "call_1", "function": {"name": "weather", "arguments": "{\"city\":\"NYC\"}"}},
+                        {"id": "call_2", "function": {"name": "weather", "arguments": "{\"city\":\"SF\"}"}},
+                    ],
+                }),
+                json!({"role": "tool", "tool_call_id": "call_1", "content": "sunny"}),
+                json!({"role": "tool", "tool_call_id": "call_2", "content": "foggy"}),
+            ],
+            tools: None,
+            tool_choice: None,
+            stream: false,
+        };
+
+        let body = AnthropicProvider.encode_request(&request);
+        let messages = body["messages"].as_array().unwrap();
+
+        // user, assistant, user (tool results merged) -- never two "user" in a row
+        assert_eq!(messages.len(), 3);
+        let roles: Vec<&str> = messages.iter().map(|m| m["role"].as_str().unwrap()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "user"]);
+
+        let tool_results = messages[2]["content"].as_array().unwrap();
+        assert_eq!(tool_results.len(), 2);
+        assert_eq!(tool_results[0]["tool_use_id"], "call_1");
+        assert_eq!(tool_results[1]["tool_use_id"], "call_2");
+    }
 }
@@ -8,9 +8,12 @@ pub mod hooks;
 pub mod loader;
 pub mod mrbot;
 pub mod mrcode;
+pub mod tool_loop;
 
 #[allow(unused_imports)] // Used by library consumers (yo binary)
 pub use loader::{load_persona, PersonaConfig, WorkspaceContext};
+#[allow(unused_imports)] // Used by library consumers (yo binary)
+pub use tool_loop::{run_tool_loop, ToolExecutor};
 
 use crate::agent::TurnResult;
 use crate::cli::Context;
@@ -0,0 +1,129 @@
+//! Multi-step tool-execution loop: drives chat -> tool calls -> chat until
+//! the model stops requesting tools or a step cap is hit.
+//!
+//! NOTE: this is implemented directly against the `llm` module's types.
+//! The real tool dispatcher and permission policy this was meant to route
+//! through aren't present in this snapshot (`crate::agent`, `crate::cli`,
+//! `crate::plan`, and this persona module's own
+//! `hooks`/`loader`/`mrbot`/`mrcode` submodules are all absent here,
+//! `crate::config` only carries `ContextConfig`, and
+//! there is no `policy` module - only the legacy, standalone prompts in
+//! `permissions.rs`). `Persona::run_turn` would call `run_tool_loop` once
+//! those pieces exist; for now this is a self-contained executor a future
+//! `ToolExecutor` impl can plug the real tool registry and policy into.
+
+use crate::llm::{ChatRequest, ChatResponse, LlmClient, ToolCall};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Maximum model<->tool round trips per turn, to bound runaway tool loops
+const DEFAULT_MAX_STEPS: u32 = 25;
+
+/// Runs individual tool calls on behalf of `run_tool_loop`. One impl per
+/// persona's real tool registry/permission system.
+pub trait ToolExecutor {
+    /// True for side-effecting tools (aichat's `may_`-prefixed convention):
+    /// these are routed through `authorize` before running; everything else
+    /// runs freely.
+    fn is_destructive(&self, tool_name: &str) -> bool {
+        tool_name.starts_with("may_")
+    }
+
+    /// Gate a destructive call through the permission system. Returning
+    /// `Ok(false)` skips execution and feeds a "denied" message back to the
+    /// model instead of aborting the turn.
+    fn authorize(&mut self, tool_name: &str, arguments: &Value) -> Result<bool>;
+
+    /// Execute `tool_name` with `arguments` and return its output
+    fn execute(&mut self, tool_name: &str, arguments: &Value) -> Result<String>;
+}
+
+/// Drives `request` through `client`/`executor`: sends the request, runs any
+/// requested tool calls, appends their results as `role: "tool"` messages,
+/// and re-sends, repeating until `finish_reason` is no longer `"tool_calls"`
+/// or `max_steps` round trips have elapsed (default `DEFAULT_MAX_STEPS`).
+///
+/// Identical `(tool_name, arguments)` calls within the same turn reuse the
+/// first call's output instead of re-executing. Tool errors are fed back as
+/// the tool message's content so the model can self-correct rather than
+/// aborting the turn.
+pub fn run_tool_loop(
+    client: &dyn LlmClient,
+    executor: &mut dyn ToolExecutor,
+    mut request: ChatRequest,
+    max_steps: Option<u32>,
+) -> Result<ChatResponse> {
+    let max_steps = max_steps.unwrap_or(DEFAULT_MAX_STEPS);
+    let mut cache: HashMap<(String, String), String> = HashMap::new();
+
+    let mut response = client.chat(&request)?;
+
+    for _ in 0..max_steps {
+        let Some(choice) = response.choices.first() else {
+            return Ok(response);
+        };
+
+        let model_requested_tools = choice.finish_reason.as_deref() == Some("tool_calls");
+        let tool_calls = match &choice.message.tool_calls {
+            Some(calls) if model_requested_tools && !calls.is_empty() => calls.clone(),
+            _ => return Ok(response),
+        };
+
+        request.messages.push(serde_json::to_value(&choice.message)?);
+
+        for tool_call in &tool_calls {
+            let output = run_one_cached(executor, &mut cache, tool_call);
+            request
+                .messages
+                .push(tool_result_message(&tool_call.id, &output));
+        }
+
+        response = client.chat(&request)?;
+    }
+
+    Ok(response)
+}
+
+fn run_one_cached(
+    executor: &mut dyn ToolExecutor,
+    cache: &mut HashMap<(String, String), String>,
+    tool_call: &ToolCall,
+) -> String {
+    let tool_name = &tool_call.function.name;
+    let raw_arguments = &tool_call.function.arguments;
+    let cache_key = (tool_name.clone(), raw_arguments.clone());
+
+    if let Some(cached) = cache.get(&cache_key) {
+        return cached.clone();
+    }
+
+    let arguments: Value =
+        serde_json::from_str(raw_arguments).unwrap_or_else(|_| Value::String(raw_arguments.clone()));
+
+    let result = if executor.is_destructive(tool_name) {
+        match executor.authorize(tool_name, &arguments) {
+            Ok(true) => executor.execute(tool_name, &arguments),
+            Ok(false) => Ok(format!("Permission denied for tool '{tool_name}'")),
+            Err(err) => Err(err),
+        }
+    } else {
+        executor.execute(tool_name, &arguments)
+    };
+
+    let output = match result {
+        Ok(output) => output,
+        Err(err) => format!("Error: {err}"),
+    };
+
+    cache.insert(cache_key, output.clone());
+    output
+}
+
+fn tool_result_message(tool_call_id: &str, content: &str) -> Value {
+    serde_json::json!({
+        "role": "tool",
+        "tool_call_id": tool_call_id,
+        "content": content,
+    })
+}
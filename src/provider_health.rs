@@ -9,8 +9,11 @@
 #![allow(dead_code)]
 
 use crate::circuit_breaker::{CircuitBreakerRegistry, CircuitState};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
@@ -27,6 +30,44 @@ pub enum HealthState {
     Unhealthy,
 }
 
+impl HealthState {
+    /// Compact encoding stored in the lock-free `probe_state` atomic
+    fn as_code(self) -> u8 {
+        match self {
+            HealthState::Healthy => 0,
+            HealthState::Degraded => 1,
+            HealthState::Unhealthy => 2,
+        }
+    }
+
+    /// Stable label used for Prometheus `state=` values
+    fn as_label(self) -> &'static str {
+        match self {
+            HealthState::Healthy => "healthy",
+            HealthState::Degraded => "degraded",
+            HealthState::Unhealthy => "unhealthy",
+        }
+    }
+
+    const ALL: [HealthState; 3] = [
+        HealthState::Healthy,
+        HealthState::Degraded,
+        HealthState::Unhealthy,
+    ];
+}
+
+/// Stable label used for Prometheus `state=` values on circuit gauges
+fn circuit_state_label(state: CircuitState) -> &'static str {
+    match state {
+        CircuitState::Closed => "closed",
+        CircuitState::Open => "open",
+        CircuitState::HalfOpen => "half_open",
+    }
+}
+
+const ALL_CIRCUIT_STATES: [CircuitState; 3] =
+    [CircuitState::Closed, CircuitState::Open, CircuitState::HalfOpen];
+
 /// Health metrics for a single provider
 #[derive(Debug, Clone, Serialize)]
 pub struct ProviderHealth {
@@ -60,6 +101,23 @@ pub struct HealthConfig {
     /// Window size for latency averaging
     #[serde(default = "default_latency_window")]
     pub latency_window: usize,
+    /// Base interval between active probes of a healthy backend.
+    /// `start_probing` takes its interval as an argument and does not read
+    /// this field.
+    #[serde(default = "default_probe_base_interval_secs")]
+    pub probe_base_interval_secs: u64,
+    /// Cap on the exponentially-backed-off probe interval for a backend
+    /// that isn't probing healthy
+    #[serde(default = "default_probe_max_interval_secs")]
+    pub probe_max_interval_secs: u64,
+    /// Consecutive successful probes required before a cooled-down backend
+    /// is marked healthy again
+    #[serde(default = "default_probe_recovery_successes")]
+    pub probe_recovery_successes: u32,
+    /// Decay constant (seconds) for the Peak-EWMA RTT estimate used by
+    /// `select_backend`: smaller values track recent latency more closely
+    #[serde(default = "default_ewma_tau_secs")]
+    pub ewma_tau_secs: f64,
 }
 
 fn default_degraded_latency_ms() -> u64 {
@@ -77,6 +135,18 @@ fn default_cooldown_secs() -> u64 {
 fn default_latency_window() -> usize {
     10
 }
+fn default_probe_base_interval_secs() -> u64 {
+    30
+}
+fn default_probe_max_interval_secs() -> u64 {
+    600
+}
+fn default_probe_recovery_successes() -> u32 {
+    3
+}
+fn default_ewma_tau_secs() -> f64 {
+    10.0
+}
 
 impl Default for HealthConfig {
     fn default() -> Self {
@@ -86,6 +156,10 @@ impl Default for HealthConfig {
             unhealthy_failure_count: default_unhealthy_failure_count(),
             cooldown_secs: default_cooldown_secs(),
             latency_window: default_latency_window(),
+            probe_base_interval_secs: default_probe_base_interval_secs(),
+            probe_max_interval_secs: default_probe_max_interval_secs(),
+            probe_recovery_successes: default_probe_recovery_successes(),
+            ewma_tau_secs: default_ewma_tau_secs(),
         }
     }
 }
@@ -103,6 +177,19 @@ struct ProviderState {
     last_success: Option<Instant>,
     last_failure: Option<Instant>,
     cooldown_until: Option<Instant>,
+    /// Consecutive successful active probes since the last non-healthy
+    /// classification, used to require N-in-a-row before reactivating
+    consecutive_probe_successes: u32,
+    /// Latest active-probe classification, readable by `is_available`
+    /// without a fresh probe round-trip
+    probe_state: AtomicU8,
+    /// Peak-EWMA RTT estimate in milliseconds, decayed toward each new
+    /// sample in `record_success`; `None` until the first sample arrives
+    ewma_rtt_ms: Option<f64>,
+    /// When `ewma_rtt_ms` was last updated, for the time-based decay factor
+    ewma_last_update: Option<Instant>,
+    /// Requests dispatched to this backend that haven't completed yet
+    outstanding: u32,
 }
 
 impl ProviderState {
@@ -156,6 +243,73 @@ impl ProviderState {
             false
         }
     }
+
+    /// Decay `ewma_rtt_ms` toward `sample_ms` using the time-based factor
+    /// `w = exp(-elapsed/tau)`, where `elapsed` is the time since the last
+    /// sample (so a stale estimate is replaced faster than a fresh one)
+    fn decay_ewma(&mut self, sample_ms: f64, tau_secs: f64) {
+        let now = Instant::now();
+        self.ewma_rtt_ms = Some(match self.ewma_rtt_ms {
+            None => sample_ms,
+            Some(prev) => {
+                let elapsed = self
+                    .ewma_last_update
+                    .map(|t| now.duration_since(t).as_secs_f64())
+                    .unwrap_or(0.0);
+                let w = (-elapsed / tau_secs).exp();
+                w * prev + (1.0 - w) * sample_ms
+            }
+        });
+        self.ewma_last_update = Some(now);
+    }
+
+    /// Peak-EWMA load cost: latency weighted by outstanding requests, so a
+    /// backend that's currently busy is penalized even if it's historically
+    /// fast. A backend with no samples yet gets zero cost, so it gets probed.
+    fn cost(&self) -> f64 {
+        self.ewma_rtt_ms.unwrap_or(0.0) * (self.outstanding as f64 + 1.0)
+    }
+}
+
+/// A cheap, provider-specific health check (e.g. a `models` list or ping
+/// endpoint) used by active background probing. Synchronous to match this
+/// crate's blocking HTTP style (see `llm::Client`), so probing just runs on
+/// its own background thread rather than requiring an async runtime.
+pub trait HealthProbe: Send + Sync {
+    /// Probe `backend` and return the observed latency, or an error if the
+    /// probe itself failed (timeout, connection refused, non-2xx, ...)
+    fn probe(&self, backend: &str) -> anyhow::Result<Duration>;
+}
+
+/// Classifies the outcome of a `HealthProbe::probe` call into a
+/// `HealthState`, so callers can treat e.g. a 429 differently from a
+/// connection refusal
+pub trait HealthLogic: Send + Sync {
+    fn classify(&self, result: &anyhow::Result<Duration>) -> HealthState;
+}
+
+/// Default classification: any probe error is unhealthy, latency over
+/// `degraded_latency_ms` is degraded, otherwise healthy
+pub struct DefaultHealthLogic {
+    config: HealthConfig,
+}
+
+impl DefaultHealthLogic {
+    pub fn new(config: HealthConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl HealthLogic for DefaultHealthLogic {
+    fn classify(&self, result: &anyhow::Result<Duration>) -> HealthState {
+        match result {
+            Err(_) => HealthState::Unhealthy,
+            Ok(latency) if latency.as_millis() as u64 > self.config.degraded_latency_ms => {
+                HealthState::Degraded
+            }
+            Ok(_) => HealthState::Healthy,
+        }
+    }
 }
 
 /// Registry of provider health tracking
@@ -182,6 +336,113 @@ impl ProviderHealthRegistry {
         self
     }
 
+    /// Spawn a background thread that actively probes every known backend on
+    /// a loop, re-probing unhealthy/degraded backends with exponentially
+    /// backed-off intervals (doubling from `interval` up to
+    /// `probe_max_interval_secs`) instead of waiting on real traffic to
+    /// reveal recovery. A backend only reactivates (clearing `cooldown_until`
+    /// and notifying the circuit breaker via `record_success`) after
+    /// `probe_recovery_successes` consecutive healthy probes.
+    pub fn start_probing(
+        self: &Arc<Self>,
+        probe: Arc<dyn HealthProbe>,
+        logic: Arc<dyn HealthLogic>,
+        interval: Duration,
+    ) -> std::thread::JoinHandle<()> {
+        let registry = Arc::clone(self);
+        std::thread::spawn(move || {
+            let mut next_due: HashMap<String, Instant> = HashMap::new();
+            let mut backoff: HashMap<String, Duration> = HashMap::new();
+            loop {
+                let backends: Vec<String> =
+                    registry.providers.read().unwrap().keys().cloned().collect();
+                let now = Instant::now();
+                for backend in backends {
+                    if next_due.get(&backend).is_some_and(|due| now < *due) {
+                        continue;
+                    }
+                    let healthy = registry.probe_once(&backend, probe.as_ref(), logic.as_ref());
+                    let next_interval = if healthy {
+                        interval
+                    } else {
+                        let prior = *backoff.get(&backend).unwrap_or(&interval);
+                        (prior * 2).min(Duration::from_secs(registry.config.probe_max_interval_secs))
+                    };
+                    backoff.insert(backend.clone(), next_interval);
+                    next_due.insert(backend, now + next_interval);
+                }
+                std::thread::sleep(interval.min(Duration::from_secs(1)));
+            }
+        })
+    }
+
+    /// Run a single active probe against `backend`, update its probe-derived
+    /// state, and reactivate it once enough consecutive probes succeed.
+    /// Returns whether this probe was classified healthy.
+    fn probe_once(&self, backend: &str, probe: &dyn HealthProbe, logic: &dyn HealthLogic) -> bool {
+        let result = probe.probe(backend);
+        let classification = logic.classify(&result);
+        let latency_ms = result.map(|d| d.as_millis() as u64).unwrap_or(0);
+
+        let mut recovered = false;
+        {
+            let mut providers = self.providers.write().unwrap();
+            let state = providers.entry(backend.to_string()).or_default();
+            state.probe_state.store(classification.as_code(), Ordering::Relaxed);
+
+            if classification == HealthState::Healthy {
+                state.consecutive_probe_successes += 1;
+                if state.consecutive_probe_successes >= self.config.probe_recovery_successes {
+                    recovered = true;
+                }
+            } else {
+                state.consecutive_probe_successes = 0;
+            }
+        }
+
+        if recovered {
+            // Clears cooldown_until, resets failure counters, and keeps the
+            // circuit breaker in sync, without touching `outstanding` or
+            // `ewma_rtt_ms` since a background probe was never paired with a
+            // `begin_request` on the dispatch path
+            self.reactivate(backend, latency_ms);
+        }
+
+        classification == HealthState::Healthy
+    }
+
+    /// Clear cooldown and reset failure bookkeeping for `backend` after a
+    /// recovered probe, and notify the circuit breaker, without the
+    /// request-completion side effects (`outstanding` decrement, EWMA decay)
+    /// that only apply to real dispatched requests. See `record_success`.
+    fn reactivate(&self, backend: &str, latency_ms: u64) {
+        {
+            let mut providers = self.providers.write().unwrap();
+            let state = providers.entry(backend.to_string()).or_default();
+
+            state.total_requests += 1;
+            state.successful_requests += 1;
+            state.consecutive_successes += 1;
+            state.consecutive_failures = 0;
+            state.last_success = Some(Instant::now());
+            state.add_latency(latency_ms, self.config.latency_window);
+            state.cooldown_until = None;
+        }
+
+        if let Some(cb) = &self.circuit_breakers {
+            cb.record_success(backend);
+        }
+    }
+
+    /// Mark a request as dispatched to `backend`, incrementing its
+    /// outstanding count for Peak-EWMA load-aware selection. Call once per
+    /// request; the matching `record_success`/`record_failure` decrements it.
+    pub fn begin_request(&self, backend: &str) {
+        let mut providers = self.providers.write().unwrap();
+        let state = providers.entry(backend.to_string()).or_default();
+        state.outstanding += 1;
+    }
+
     /// Record a successful request
     pub fn record_success(&self, backend: &str, latency_ms: u64) {
         let mut providers = self.providers.write().unwrap();
@@ -193,6 +454,8 @@ impl ProviderHealthRegistry {
         state.consecutive_failures = 0;
         state.last_success = Some(Instant::now());
         state.add_latency(latency_ms, self.config.latency_window);
+        state.outstanding = state.outstanding.saturating_sub(1);
+        state.decay_ewma(latency_ms as f64, self.config.ewma_tau_secs);
 
         // Clear cooldown on success
         state.cooldown_until = None;
@@ -213,6 +476,7 @@ impl ProviderHealthRegistry {
         state.consecutive_failures += 1;
         state.consecutive_successes = 0;
         state.last_failure = Some(Instant::now());
+        state.outstanding = state.outstanding.saturating_sub(1);
 
         // Set cooldown if becoming unhealthy
         if state.consecutive_failures >= self.config.unhealthy_failure_count {
@@ -291,6 +555,11 @@ impl ProviderHealthRegistry {
 
         let providers = self.providers.read().unwrap();
         if let Some(state) = providers.get(backend) {
+            // Active probing may have already marked this backend unhealthy;
+            // read that directly rather than waiting for a fresh probe
+            if state.probe_state.load(Ordering::Relaxed) == HealthState::Unhealthy.as_code() {
+                return false;
+            }
             // Not available if in cooldown
             if state.is_in_cooldown() {
                 return false;
@@ -313,6 +582,46 @@ impl ProviderHealthRegistry {
             .collect()
     }
 
+    /// Pick the best backend among `backends` via Peak-EWMA with
+    /// power-of-two-choices: filter to available backends, sample two
+    /// distinct candidates at random, and return the one with the lower
+    /// `ewma_rtt_ms * (outstanding + 1)` cost. This naturally steers traffic
+    /// away from degraded-but-not-unhealthy providers without needing a
+    /// global view of every backend's load on each pick.
+    pub fn select_backend(&self, backends: &[String]) -> Option<String> {
+        let available = self.filter_available(backends);
+        match available.len() {
+            0 => None,
+            1 => Some(available[0].clone()),
+            n => {
+                let mut rng = rand::thread_rng();
+                let i = rng.gen_range(0..n);
+                let mut j = rng.gen_range(0..n - 1);
+                if j >= i {
+                    j += 1;
+                }
+
+                let a = &available[i];
+                let b = &available[j];
+                if self.cost(a) <= self.cost(b) {
+                    Some(a.clone())
+                } else {
+                    Some(b.clone())
+                }
+            }
+        }
+    }
+
+    /// Peak-EWMA cost for a backend; zero for one with no recorded samples
+    fn cost(&self, backend: &str) -> f64 {
+        self.providers
+            .read()
+            .unwrap()
+            .get(backend)
+            .map(|s| s.cost())
+            .unwrap_or(0.0)
+    }
+
     /// Get combined health and circuit breaker status
     pub fn get_status(&self, backend: &str) -> ProviderStatus {
         let health = self.get_health(backend);
@@ -330,6 +639,113 @@ impl ProviderHealthRegistry {
             available: self.is_available(backend),
         }
     }
+
+    /// Render per-backend health and circuit breaker metrics in Prometheus
+    /// text exposition format, suitable for scraping on an interval.
+    pub fn render_prometheus(&self) -> String {
+        let infos = self.all_health_info();
+        // Rough capacity estimate: ~6 lines of metrics plus 6 state-gauge
+        // lines per backend, ~60 bytes each
+        let mut out = String::with_capacity(infos.len() * 12 * 60);
+
+        out.push_str("# HELP brainpro_provider_requests_total Total requests sent to a provider backend\n");
+        out.push_str("# TYPE brainpro_provider_requests_total counter\n");
+        for info in &infos {
+            let _ = writeln!(
+                out,
+                "brainpro_provider_requests_total{{backend=\"{}\"}} {}",
+                info.backend, info.total_requests
+            );
+        }
+
+        out.push_str("# HELP brainpro_provider_requests_successful_total Successful requests to a provider backend\n");
+        out.push_str("# TYPE brainpro_provider_requests_successful_total counter\n");
+        for info in &infos {
+            let _ = writeln!(
+                out,
+                "brainpro_provider_requests_successful_total{{backend=\"{}\"}} {}",
+                info.backend, info.successful_requests
+            );
+        }
+
+        out.push_str(
+            "# HELP brainpro_provider_requests_failed_total Failed requests to a provider backend\n",
+        );
+        out.push_str("# TYPE brainpro_provider_requests_failed_total counter\n");
+        for info in &infos {
+            let _ = writeln!(
+                out,
+                "brainpro_provider_requests_failed_total{{backend=\"{}\"}} {}",
+                info.backend, info.failed_requests
+            );
+        }
+
+        out.push_str(
+            "# HELP brainpro_provider_consecutive_failures Current consecutive failure count for a provider backend\n",
+        );
+        out.push_str("# TYPE brainpro_provider_consecutive_failures gauge\n");
+        for info in &infos {
+            let _ = writeln!(
+                out,
+                "brainpro_provider_consecutive_failures{{backend=\"{}\"}} {}",
+                info.backend, info.consecutive_failures
+            );
+        }
+
+        out.push_str(
+            "# HELP brainpro_provider_avg_latency_ms Rolling average latency for a provider backend, in milliseconds\n",
+        );
+        out.push_str("# TYPE brainpro_provider_avg_latency_ms gauge\n");
+        for info in &infos {
+            let _ = writeln!(
+                out,
+                "brainpro_provider_avg_latency_ms{{backend=\"{}\"}} {}",
+                info.backend, info.avg_latency_ms
+            );
+        }
+
+        out.push_str(
+            "# HELP brainpro_provider_health_state Health state of a provider backend (1 = current state)\n",
+        );
+        out.push_str("# TYPE brainpro_provider_health_state gauge\n");
+        for info in &infos {
+            for state in HealthState::ALL {
+                let value = if info.state == state { 1 } else { 0 };
+                let _ = writeln!(
+                    out,
+                    "brainpro_provider_health_state{{backend=\"{}\",state=\"{}\"}} {}",
+                    info.backend,
+                    state.as_label(),
+                    value
+                );
+            }
+        }
+
+        out.push_str(
+            "# HELP brainpro_provider_circuit_state Circuit breaker state of a provider backend (1 = current state)\n",
+        );
+        out.push_str("# TYPE brainpro_provider_circuit_state gauge\n");
+        for info in &infos {
+            let circuit_state = self
+                .circuit_breakers
+                .as_ref()
+                .and_then(|cb| cb.stats(&info.backend))
+                .map(|s| s.state)
+                .unwrap_or(CircuitState::Closed);
+            for state in ALL_CIRCUIT_STATES {
+                let value = if circuit_state == state { 1 } else { 0 };
+                let _ = writeln!(
+                    out,
+                    "brainpro_provider_circuit_state{{backend=\"{}\",state=\"{}\"}} {}",
+                    info.backend,
+                    circuit_state_label(state),
+                    value
+                );
+            }
+        }
+
+        out
+    }
 }
 
 impl Default for ProviderHealthRegistry {
@@ -450,4 +866,182 @@ mod tests {
         assert!(!available.contains(&"backend2".to_string()));
         assert!(available.contains(&"backend3".to_string()));
     }
+
+    struct DummyProbe;
+
+    impl HealthProbe for DummyProbe {
+        fn probe(&self, _backend: &str) -> anyhow::Result<Duration> {
+            Ok(Duration::from_millis(5))
+        }
+    }
+
+    struct FailingProbe;
+
+    impl HealthProbe for FailingProbe {
+        fn probe(&self, _backend: &str) -> anyhow::Result<Duration> {
+            Err(anyhow::anyhow!("connection refused"))
+        }
+    }
+
+    #[test]
+    fn test_default_health_logic_classifies_latency_and_errors() {
+        let logic = DefaultHealthLogic::new(HealthConfig {
+            degraded_latency_ms: 100,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            logic.classify(&Ok(Duration::from_millis(10))),
+            HealthState::Healthy
+        );
+        assert_eq!(
+            logic.classify(&Ok(Duration::from_millis(200))),
+            HealthState::Degraded
+        );
+        assert_eq!(
+            logic.classify(&Err(anyhow::anyhow!("refused"))),
+            HealthState::Unhealthy
+        );
+    }
+
+    #[test]
+    fn test_probe_once_marks_unhealthy_on_probe_error() {
+        let registry = ProviderHealthRegistry::default();
+        assert!(registry.is_available("test"));
+
+        let logic = DefaultHealthLogic::new(HealthConfig::default());
+        let healthy = registry.probe_once("test", &FailingProbe, &logic);
+
+        assert!(!healthy);
+        assert!(!registry.is_available("test"));
+    }
+
+    #[test]
+    fn test_probe_once_reactivates_after_consecutive_successes() {
+        let config = HealthConfig {
+            unhealthy_failure_count: 1,
+            cooldown_secs: 60,
+            probe_recovery_successes: 2,
+            ..Default::default()
+        };
+        let registry = ProviderHealthRegistry::new(config.clone());
+        registry.record_failure("test"); // immediately unhealthy + in cooldown
+        assert!(!registry.is_available("test"));
+
+        let logic = DefaultHealthLogic::new(config);
+
+        // First successful probe isn't enough to reactivate yet
+        assert!(registry.probe_once("test", &DummyProbe, &logic));
+        assert!(!registry.is_available("test"));
+
+        // Second consecutive success clears cooldown and reactivates
+        assert!(registry.probe_once("test", &DummyProbe, &logic));
+        assert!(registry.is_available("test"));
+    }
+
+    #[test]
+    fn test_probe_once_reactivation_does_not_touch_outstanding_or_ewma() {
+        let config = HealthConfig {
+            unhealthy_failure_count: 1,
+            cooldown_secs: 60,
+            probe_recovery_successes: 1,
+            ..Default::default()
+        };
+        let registry = ProviderHealthRegistry::new(config.clone());
+
+        // Two in-flight dispatched requests; the failing one completes and
+        // decrements outstanding, leaving one still outstanding
+        registry.begin_request("test");
+        registry.begin_request("test");
+        registry.record_failure("test"); // immediately unhealthy + in cooldown
+
+        let logic = DefaultHealthLogic::new(config);
+        assert!(registry.probe_once("test", &DummyProbe, &logic));
+        assert!(registry.is_available("test"));
+
+        let providers = registry.providers.read().unwrap();
+        let state = providers.get("test").unwrap();
+        // A probe reactivation must not decrement outstanding again (it was
+        // never paired with begin_request) or fold probe RTT into the EWMA
+        // used for load-aware selection
+        assert_eq!(state.outstanding, 1);
+        assert!(state.ewma_rtt_ms.is_none());
+    }
+
+    #[test]
+    fn test_select_backend_prefers_lower_cost() {
+        let registry = ProviderHealthRegistry::default();
+
+        registry.record_success("fast", 10);
+        registry.record_success("slow", 1000);
+
+        let backends = vec!["fast".to_string(), "slow".to_string()];
+        // With only two candidates, power-of-two-choices always compares
+        // both, so the lower-cost backend wins deterministically
+        for _ in 0..20 {
+            assert_eq!(
+                registry.select_backend(&backends),
+                Some("fast".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_backend_penalizes_outstanding_requests() {
+        let registry = ProviderHealthRegistry::default();
+
+        registry.record_success("a", 50);
+        registry.record_success("b", 50);
+        // Pile up in-flight requests on "a" so its cost rises above "b"'s
+        for _ in 0..5 {
+            registry.begin_request("a");
+        }
+
+        let backends = vec!["a".to_string(), "b".to_string()];
+        for _ in 0..20 {
+            assert_eq!(registry.select_backend(&backends), Some("b".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_select_backend_returns_only_available_candidate() {
+        let registry = ProviderHealthRegistry::default();
+        let backends = vec!["solo".to_string()];
+        assert_eq!(registry.select_backend(&backends), Some("solo".to_string()));
+    }
+
+    #[test]
+    fn test_select_backend_skips_unavailable() {
+        let config = HealthConfig {
+            unhealthy_failure_count: 1,
+            ..Default::default()
+        };
+        let registry = ProviderHealthRegistry::new(config);
+        registry.record_failure("down");
+
+        let backends = vec!["down".to_string(), "up".to_string()];
+        assert_eq!(registry.select_backend(&backends), Some("up".to_string()));
+    }
+
+    #[test]
+    fn test_render_prometheus_includes_counters_and_state_gauges() {
+        let registry = ProviderHealthRegistry::default();
+        registry.record_success("openai", 120);
+        registry.record_failure("openai");
+
+        let rendered = registry.render_prometheus();
+
+        assert!(rendered.contains("# TYPE brainpro_provider_requests_total counter"));
+        assert!(rendered.contains("brainpro_provider_requests_total{backend=\"openai\"} 2"));
+        assert!(rendered.contains("brainpro_provider_requests_successful_total{backend=\"openai\"} 1"));
+        assert!(rendered.contains("brainpro_provider_requests_failed_total{backend=\"openai\"} 1"));
+        assert!(rendered.contains("brainpro_provider_consecutive_failures{backend=\"openai\"} 1"));
+        assert!(rendered
+            .contains("brainpro_provider_health_state{backend=\"openai\",state=\"healthy\"} 1"));
+        assert!(rendered
+            .contains("brainpro_provider_circuit_state{backend=\"openai\",state=\"closed\"} 1"));
+        // Non-current states are emitted as explicit zeroes, not omitted
+        assert!(rendered
+            .contains("brainpro_provider_health_state{backend=\"openai\",state=\"unhealthy\"} 0"));
+    }
 }
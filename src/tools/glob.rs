@@ -1,6 +1,8 @@
 use super::SchemaOptions;
+use ignore::WalkBuilder;
 use serde_json::{json, Value};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 pub fn schema(opts: &SchemaOptions) -> Value {
     if opts.optimize {
@@ -13,7 +15,9 @@ pub fn schema(opts: &SchemaOptions) -> Value {
                     "type": "object",
                     "properties": {
                         "pattern": { "type": "string" },
-                        "max_results": { "type": "integer" }
+                        "max_results": { "type": "integer" },
+                        "fuzzy": { "type": "boolean" },
+                        "include_metadata": { "type": "boolean" }
                     },
                     "required": ["pattern"]
                 }
@@ -28,8 +32,10 @@ pub fn schema(opts: &SchemaOptions) -> Value {
                 "parameters": {
                     "type": "object",
                     "properties": {
-                        "pattern": { "type": "string", "description": "Glob pattern (e.g. **/*.rs)" },
-                        "max_results": { "type": "integer", "description": "Max files (default 2000)" }
+                        "pattern": { "type": "string", "description": "Glob pattern (e.g. **/*.rs), or a fuzzy query (e.g. chatctx) when fuzzy is true" },
+                        "max_results": { "type": "integer", "description": "Max files (default 2000)" },
+                        "fuzzy": { "type": "boolean", "description": "Rank files by subsequence match against pattern instead of literal glob expansion (default false)" },
+                        "include_metadata": { "type": "boolean", "description": "Return {path, size, mime, modified} objects instead of plain path strings (default false)" }
                     },
                     "required": ["pattern"]
                 }
@@ -41,11 +47,15 @@ pub fn schema(opts: &SchemaOptions) -> Value {
 pub fn execute(args: Value, root: &Path) -> anyhow::Result<Value> {
     let pattern = args["pattern"].as_str().unwrap_or("");
     let max_results = args["max_results"].as_u64().unwrap_or(2000) as usize;
+    let fuzzy = args["fuzzy"].as_bool().unwrap_or(false);
+    let include_metadata = args["include_metadata"].as_bool().unwrap_or(false);
 
-    let full_pattern = root.join(pattern).to_string_lossy().to_string();
+    if fuzzy {
+        return execute_fuzzy(pattern, max_results, root, include_metadata);
+    }
 
-    let entries = match glob::glob(&full_pattern) {
-        Ok(e) => e,
+    let glob_pattern = match glob::Pattern::new(pattern) {
+        Ok(p) => p,
         Err(e) => {
             return Ok(json!({ "error": { "code": "invalid_glob", "message": e.to_string() } }))
         }
@@ -54,32 +64,210 @@ pub fn execute(args: Value, root: &Path) -> anyhow::Result<Value> {
     let mut paths = Vec::new();
     let mut truncated = false;
 
-    for entry in entries {
-        let path = match entry {
-            Ok(p) => p,
-            Err(_) => continue,
-        };
-
-        if is_excluded(&path, root) {
+    for path in walk_files(root) {
+        let rel = path.strip_prefix(root).unwrap_or(&path);
+        if !glob_pattern.matches_path(rel) {
             continue;
         }
 
-        let rel = path.strip_prefix(root).unwrap_or(&path);
-
         if paths.len() >= max_results {
             truncated = true;
             break;
         }
 
-        paths.push(rel.to_string_lossy().to_string());
+        paths.push(result_entry(rel, &path, include_metadata));
+    }
+
+    Ok(json!({
+        "paths": paths,
+        "truncated": truncated
+    }))
+}
+
+/// Rank every file under `root` by subsequence-match score against `query`,
+/// returning the top `max_results` paths. Cheaply rejects candidates whose
+/// `char_bag` doesn't contain every query character before running the
+/// (more expensive) DP scoring pass on survivors.
+fn execute_fuzzy(
+    query: &str,
+    max_results: usize,
+    root: &Path,
+    include_metadata: bool,
+) -> anyhow::Result<Value> {
+    let query_lower: Vec<char> = query.to_lowercase().chars().collect();
+    let query_bag = char_bag(&query_lower);
+
+    let mut scored: Vec<(i64, PathBuf, String)> = Vec::new();
+
+    for path in walk_files(root) {
+        let rel = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+
+        let path_lower: Vec<char> = rel.to_lowercase().chars().collect();
+        if char_bag(&path_lower) & query_bag != query_bag {
+            continue;
+        }
+
+        let path_chars: Vec<char> = rel.chars().collect();
+        if let Some(score) = fuzzy_score(&query_lower, &path_chars, &path_lower) {
+            scored.push((score, path, rel));
+        }
     }
 
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.cmp(&b.2)));
+    let truncated = scored.len() > max_results;
+    let paths: Vec<Value> = scored
+        .into_iter()
+        .take(max_results)
+        .map(|(_, path, rel)| result_entry(Path::new(&rel), &path, include_metadata))
+        .collect();
+
     Ok(json!({
         "paths": paths,
         "truncated": truncated
     }))
 }
 
+/// Build one result entry: a plain path string by default, or a
+/// `{path, size, mime, modified}` object when `include_metadata` is set, so
+/// callers can pre-filter (skip binaries, prefer source files) without a
+/// follow-up read.
+fn result_entry(rel: &Path, absolute: &Path, include_metadata: bool) -> Value {
+    let rel_str = rel.to_string_lossy().to_string();
+    if !include_metadata {
+        return Value::String(rel_str);
+    }
+
+    let meta = std::fs::metadata(absolute).ok();
+    let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
+    let modified = meta
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+    let mime = mime_guess::from_path(absolute)
+        .first_or_octet_stream()
+        .to_string();
+
+    json!({
+        "path": rel_str,
+        "size": size,
+        "mime": mime,
+        "modified": modified
+    })
+}
+
+/// Walk every file under `root`, honoring the repository's `.gitignore`/
+/// `.ignore` rules (layered per directory, via the `ignore` crate) as well
+/// as the hardcoded baseline excludes in `is_excluded`. Hidden files and
+/// directories are skipped too (the `ignore` crate's default).
+fn walk_files(root: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(root)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+        .map(|entry| entry.into_path())
+        .filter(|path| !is_excluded(path, root))
+        .collect()
+}
+
+/// A 64-bit mask with one bit per lowercased ASCII letter/digit present in
+/// `chars`. Used to cheaply reject candidates that can't possibly contain
+/// every query character before the DP scoring pass runs.
+fn char_bag(chars: &[char]) -> u64 {
+    let mut bag = 0u64;
+    for &c in chars {
+        if let Some(bit) = char_bit(c) {
+            bag |= 1 << bit;
+        }
+    }
+    bag
+}
+
+fn char_bit(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        '0'..='9' => Some(26 + (c as u32 - '0' as u32)),
+        _ => None,
+    }
+}
+
+/// Score `path_chars` as a fuzzy subsequence match against `query_lower` via
+/// a DP pass that rewards consecutive matches, gives a bonus when a matched
+/// character follows a path separator / `_` / `-` / a camelCase boundary,
+/// and penalizes gaps between matches and overall path length. Returns
+/// `None` if `query_lower` isn't a subsequence of `path_lower` at all (the
+/// `char_bag` pre-filter only guarantees the right characters are present,
+/// not that they appear in order).
+fn fuzzy_score(query_lower: &[char], path_chars: &[char], path_lower: &[char]) -> Option<i64> {
+    const BOUNDARY_BONUS: i64 = 32;
+    const CONSECUTIVE_BONUS: i64 = 24;
+    const GAP_PENALTY: i64 = 2;
+    const NEG_INF: i64 = i64::MIN / 4;
+
+    let n = query_lower.len();
+    let m = path_lower.len();
+    if n == 0 {
+        return Some(0);
+    }
+    if m < n {
+        return None;
+    }
+
+    // Per-position bonus for matching at that path character.
+    let mut bonus = vec![0i64; m];
+    for j in 0..m {
+        bonus[j] = if j == 0 {
+            BOUNDARY_BONUS
+        } else {
+            let prev = path_chars[j - 1];
+            let cur = path_chars[j];
+            if prev == '/' || prev == '_' || prev == '-' || prev == '.' {
+                BOUNDARY_BONUS
+            } else if prev.is_lowercase() && cur.is_uppercase() {
+                BOUNDARY_BONUS
+            } else {
+                0
+            }
+        };
+    }
+
+    // best[i][j]: best score matching query[..i] against path[..j]
+    // match_at[i][j]: best score matching query[..i] against path[..j] where
+    // query[i-1] is matched exactly at path position j-1
+    let mut best = vec![vec![0i64; m + 1]; n + 1];
+    let mut match_at = vec![vec![NEG_INF; m + 1]; n + 1];
+    for row in best.iter_mut().skip(1) {
+        row[0] = NEG_INF;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let skip = best[i][j - 1] - GAP_PENALTY;
+
+            if path_lower[j - 1] == query_lower[i - 1] {
+                let consecutive = match_at[i - 1][j - 1] + CONSECUTIVE_BONUS;
+                let fresh = best[i - 1][j - 1];
+                let m_score = bonus[j - 1] + consecutive.max(fresh);
+                match_at[i][j] = m_score;
+                best[i][j] = skip.max(m_score);
+            } else {
+                best[i][j] = skip;
+            }
+        }
+    }
+
+    let score = best[n][m];
+    if score <= NEG_INF / 2 {
+        None
+    } else {
+        Some(score - path_chars.len() as i64 / 4)
+    }
+}
+
 fn is_excluded(path: &Path, root: &Path) -> bool {
     let rel = path.strip_prefix(root).unwrap_or(path);
     for component in rel.components() {
@@ -1,15 +1,90 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::{File, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+#[cfg(feature = "admin-api")]
+use std::sync::{mpsc, Arc, Mutex};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Seed `prev_hash` for the first record in a hash-chained transcript
+const GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sign_hmac(key: &[u8], message: &str) -> String {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts a key of any size");
+    mac.update(message.as_bytes());
+    to_hex(&mac.finalize().into_bytes())
+}
+
+/// Per-transcript hash-chaining state, present only when `Transcript` was
+/// created via `new_chained`/`resume`
+struct ChainState {
+    last_hash: String,
+    hmac_key: Option<Vec<u8>>,
+}
+
+/// Live fan-out of newly-logged lines to admin API subscribers (e.g. the SSE
+/// transcript tail), keyed by the subscriber's optional session filter so
+/// multiple concurrent sessions can be watched independently. `std::sync::mpsc`
+/// rather than `tokio::sync::broadcast`, to match this crate's synchronous
+/// style (see `llm::Client`) instead of pulling in an async runtime.
+#[cfg(feature = "admin-api")]
+#[derive(Default)]
+struct Broadcast {
+    subscribers: Mutex<Vec<(Option<String>, mpsc::Sender<String>)>>,
+}
+
+#[cfg(feature = "admin-api")]
+impl Broadcast {
+    fn subscribe(&self, session_id: Option<String>) -> mpsc::Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push((session_id, tx));
+        rx
+    }
+
+    fn publish(&self, session_id: &str, line: &str) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|(filter, tx)| {
+            if filter.as_deref().is_some_and(|f| f != session_id) {
+                return true;
+            }
+            tx.send(line.to_string()).is_ok()
+        });
+    }
+}
+
+/// A cloneable handle for subscribing to a `Transcript`'s live event feed,
+/// independent of the `&mut self` needed to log new events.
+#[cfg(feature = "admin-api")]
+#[derive(Clone, Default)]
+pub struct TranscriptFeed(Arc<Broadcast>);
+
+#[cfg(feature = "admin-api")]
+impl TranscriptFeed {
+    /// Subscribe to newly logged lines. When `session_id` is `Some`, only
+    /// events from that session are delivered.
+    pub fn subscribe(&self, session_id: Option<String>) -> mpsc::Receiver<String> {
+        self.0.subscribe(session_id)
+    }
+}
 
 pub struct Transcript {
     pub path: PathBuf,
     session_id: String,
     cwd: PathBuf,
     file: File,
+    chain: Option<ChainState>,
+    #[cfg(feature = "admin-api")]
+    feed: TranscriptFeed,
 }
 
 #[derive(Serialize)]
@@ -21,6 +96,49 @@ struct Event<'a> {
     event_type: &'a str,
     #[serde(flatten)]
     data: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+}
+
+/// Owned mirror of `Event`, used to re-read and re-hash logged lines in
+/// `verify`/`resume`. Field order must stay in sync with `Event` so the
+/// recomputed canonical JSON matches what was originally hashed.
+#[derive(Serialize, Deserialize)]
+struct LoggedEvent {
+    ts: DateTime<Utc>,
+    session_id: String,
+    cwd: PathBuf,
+    #[serde(rename = "type")]
+    event_type: String,
+    #[serde(flatten)]
+    data: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+}
+
+/// Result of `Transcript::verify`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyReport {
+    /// Number of non-empty lines read from the transcript
+    pub total_events: usize,
+    /// Index of the first line whose `prev_hash`/`hash` doesn't match the
+    /// recomputed chain, if any
+    pub broken_at: Option<usize>,
+    /// Hash of the last successfully verified line
+    pub terminal_hash: Option<String>,
+    /// HMAC of `terminal_hash`, present when a key was supplied to `verify`
+    pub terminal_signature: Option<String>,
+}
+
+impl VerifyReport {
+    /// True if the chain was unbroken end to end
+    pub fn is_intact(&self) -> bool {
+        self.broken_at.is_none()
+    }
 }
 
 impl Transcript {
@@ -32,20 +150,155 @@ impl Transcript {
             session_id: session_id.to_string(),
             cwd: cwd.to_path_buf(),
             file,
+            chain: None,
+            #[cfg(feature = "admin-api")]
+            feed: TranscriptFeed::default(),
+        })
+    }
+
+    /// Handle for subscribing to this transcript's live event feed (e.g. for
+    /// an SSE tail), independent of the `&mut self` needed to log events.
+    #[cfg(feature = "admin-api")]
+    pub fn feed(&self) -> TranscriptFeed {
+        self.feed.clone()
+    }
+
+    /// Like `new`, but chains each event's hash from a zero seed so
+    /// truncation, reordering, or after-the-fact edits of the log become
+    /// detectable via `verify`. `hmac_key` is stored with the chain state
+    /// but not used when logging; a signed terminal hash comes from
+    /// `verify` when it is given a key.
+    pub fn new_chained(
+        path: &Path,
+        session_id: &str,
+        cwd: &Path,
+        hmac_key: Option<Vec<u8>>,
+    ) -> Result<Self> {
+        let mut transcript = Self::new(path, session_id, cwd)?;
+        transcript.chain = Some(ChainState {
+            last_hash: GENESIS_HASH.to_string(),
+            hmac_key,
+        });
+        Ok(transcript)
+    }
+
+    /// Resume a hash-chained transcript across a process restart: reads the
+    /// last line's `hash` so new events continue the existing chain instead
+    /// of starting a fresh one (which `verify` would otherwise flag as a
+    /// break at the restart point).
+    pub fn resume(
+        path: &Path,
+        session_id: &str,
+        cwd: &Path,
+        hmac_key: Option<Vec<u8>>,
+    ) -> Result<Self> {
+        let last_hash = last_line_hash(path)?.unwrap_or_else(|| GENESIS_HASH.to_string());
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            session_id: session_id.to_string(),
+            cwd: cwd.to_path_buf(),
+            file,
+            chain: Some(ChainState {
+                last_hash,
+                hmac_key,
+            }),
+            #[cfg(feature = "admin-api")]
+            feed: TranscriptFeed::default(),
+        })
+    }
+
+    /// Re-read `path`, recompute the hash chain, and report the first index
+    /// where it breaks (or that it's intact end to end)
+    pub fn verify(path: &Path, hmac_key: Option<&[u8]>) -> Result<VerifyReport> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading transcript at {}", path.display()))?;
+
+        let mut expected_prev = GENESIS_HASH.to_string();
+        let mut broken_at = None;
+        let mut terminal_hash = None;
+        let mut total_events = 0usize;
+
+        for (idx, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            total_events += 1;
+            if broken_at.is_some() {
+                continue;
+            }
+
+            let mut event: LoggedEvent = serde_json::from_str(line)
+                .with_context(|| format!("parsing transcript line {}", idx))?;
+            let recorded_hash = event.hash.take();
+            let recorded_prev = event.prev_hash.clone();
+
+            if recorded_prev.as_deref() != Some(expected_prev.as_str()) {
+                broken_at = Some(idx);
+                continue;
+            }
+
+            let body = serde_json::to_string(&event)?;
+            let mut hasher = Sha256::new();
+            hasher.update(expected_prev.as_bytes());
+            hasher.update(body.as_bytes());
+            let computed = to_hex(&hasher.finalize());
+
+            if recorded_hash.as_deref() != Some(computed.as_str()) {
+                broken_at = Some(idx);
+                continue;
+            }
+
+            expected_prev = computed.clone();
+            terminal_hash = Some(computed);
+        }
+
+        let terminal_signature = match (hmac_key, &terminal_hash) {
+            (Some(key), Some(hash)) => Some(sign_hmac(key, hash)),
+            _ => None,
+        };
+
+        Ok(VerifyReport {
+            total_events,
+            broken_at,
+            terminal_hash,
+            terminal_signature,
         })
     }
 
     pub fn log(&mut self, event_type: &str, data: serde_json::Value) -> Result<()> {
-        let event = Event {
+        let prev_hash = self.chain.as_ref().map(|c| c.last_hash.clone());
+
+        let mut event = Event {
             ts: Utc::now(),
             session_id: &self.session_id,
             cwd: &self.cwd,
             event_type,
             data,
+            prev_hash,
+            hash: None,
         };
+
+        if let Some(chain) = &self.chain {
+            let body = serde_json::to_string(&event)?;
+            let mut hasher = Sha256::new();
+            hasher.update(chain.last_hash.as_bytes());
+            hasher.update(body.as_bytes());
+            event.hash = Some(to_hex(&hasher.finalize()));
+        }
+
         let line = serde_json::to_string(&event)?;
         writeln!(self.file, "{}", line)?;
         self.file.flush()?;
+
+        if let Some(chain) = &mut self.chain {
+            chain.last_hash = event.hash.clone().expect("hash computed above when chained");
+        }
+
+        #[cfg(feature = "admin-api")]
+        self.feed.0.publish(&self.session_id, &line);
+
         Ok(())
     }
 
@@ -146,3 +399,22 @@ impl Transcript {
         self.log("error", serde_json::json!({ "message": message }))
     }
 }
+
+/// Read the last non-empty line of an existing transcript and return its
+/// `hash` field, if any. Returns `Ok(None)` when the file doesn't exist yet
+/// or has no events, so `resume` can fall back to starting a fresh chain.
+fn last_line_hash(path: &Path) -> Result<Option<String>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let Some(last_line) = content.lines().rev().find(|line| !line.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    let event: LoggedEvent =
+        serde_json::from_str(last_line).context("parsing last transcript line")?;
+    Ok(event.hash)
+}
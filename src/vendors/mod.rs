@@ -0,0 +1,153 @@
+//! Multi-provider model pricing registry.
+//!
+//! Each backend that can report model pricing implements `PricingSource`.
+//! `get_pricing` selects the right source by provider name and caches its
+//! result to `~/.yo/pricing/<provider>.json`, keeping the 1-week staleness
+//! window and stale-on-failure fallback the original Venice-only cache had.
+
+pub mod openai;
+pub mod venice;
+
+use crate::cost::ModelPricing;
+use crate::llm::Usage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Cache expiry duration (1 week), shared by every `PricingSource`.
+const CACHE_MAX_AGE_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// A backend that can supply model pricing, fetched live or from a static
+/// fallback table, keyed under its own cache file.
+pub trait PricingSource {
+    /// Provider name, used as the cache file key (`~/.yo/pricing/<name>.json`)
+    fn provider_name(&self) -> &'static str;
+
+    /// Fetch current pricing fresh (from an API, or a static table for
+    /// providers whose model-listing endpoint doesn't expose prices).
+    fn fetch(&self) -> anyhow::Result<HashMap<String, ModelPricing>>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PricingCache {
+    /// Unix timestamp when this cache was fetched
+    fetched_at: u64,
+    models: HashMap<String, ModelPricing>,
+}
+
+impl PricingCache {
+    fn is_valid(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_sub(self.fetched_at) < CACHE_MAX_AGE_SECS
+    }
+}
+
+fn cache_path(provider: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".yo").join("pricing").join(format!("{provider}.json")))
+}
+
+fn load_cache(provider: &str) -> Option<PricingCache> {
+    let path = cache_path(provider)?;
+    let content = fs::read_to_string(&path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cache(provider: &str, cache: &PricingCache) -> anyhow::Result<()> {
+    let path =
+        cache_path(provider).ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = serde_json::to_string_pretty(cache)?;
+    fs::write(&path, content)?;
+    Ok(())
+}
+
+fn source_for(provider: &str) -> Option<Box<dyn PricingSource>> {
+    match provider {
+        "venice" => Some(Box::new(venice::VenicePricingSource)),
+        "openai" => Some(Box::new(openai::OpenAiPricingSource)),
+        _ => None,
+    }
+}
+
+/// Get pricing for `provider`, using its on-disk cache if valid, refetching
+/// if stale, and falling back to a stale cache if the refetch fails.
+/// Returns `None` if `provider` has no registered source or both cache and
+/// fetch fail.
+pub fn get_pricing(provider: &str) -> Option<HashMap<String, ModelPricing>> {
+    let source = source_for(provider)?;
+    let name = source.provider_name();
+
+    if let Some(cache) = load_cache(name) {
+        if cache.is_valid() {
+            return Some(cache.models);
+        }
+    }
+
+    match source.fetch() {
+        Ok(models) => {
+            let cache = PricingCache {
+                fetched_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                models: models.clone(),
+            };
+
+            // Save cache (ignore errors - not critical)
+            let _ = save_cache(name, &cache);
+
+            Some(models)
+        }
+        Err(_) => {
+            // Fetch failed, fall back to a stale cache if we have one
+            load_cache(name).map(|c| c.models)
+        }
+    }
+}
+
+/// Compute the USD cost of a completed call: looks up `model`'s pricing in
+/// `provider`'s table and applies it to `usage` (see `LlmCallResult`).
+/// Returns `None` if the provider or model has no known pricing.
+pub fn cost_for_call(provider: &str, model: &str, usage: &Usage) -> Option<f64> {
+    let table = get_pricing(provider)?;
+    table.get(model).map(|pricing| pricing.cost_usd(usage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_validity() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let fresh = PricingCache {
+            fetched_at: now,
+            models: HashMap::new(),
+        };
+        assert!(fresh.is_valid());
+
+        let old = PricingCache {
+            fetched_at: now - CACHE_MAX_AGE_SECS - 1,
+            models: HashMap::new(),
+        };
+        assert!(!old.is_valid());
+    }
+
+    #[test]
+    fn test_unknown_provider_returns_none() {
+        assert!(source_for("not-a-real-provider").is_none());
+    }
+}
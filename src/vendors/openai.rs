@@ -0,0 +1,30 @@
+//! OpenAI pricing source.
+//!
+//! OpenAI's `/models` endpoint doesn't expose pricing, so this is a static
+//! fallback table (USD per 1M tokens) updated by hand when OpenAI changes
+//! prices, rather than fetched live like `venice::VenicePricingSource`.
+
+use super::PricingSource;
+use crate::cost::ModelPricing;
+use std::collections::HashMap;
+
+pub struct OpenAiPricingSource;
+
+impl PricingSource for OpenAiPricingSource {
+    fn provider_name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn fetch(&self) -> anyhow::Result<HashMap<String, ModelPricing>> {
+        Ok(static_table())
+    }
+}
+
+fn static_table() -> HashMap<String, ModelPricing> {
+    let mut table = HashMap::new();
+    table.insert("gpt-4o".to_string(), ModelPricing::new(2.50, 10.00));
+    table.insert("gpt-4o-mini".to_string(), ModelPricing::new(0.15, 0.60));
+    table.insert("gpt-4-turbo".to_string(), ModelPricing::new(10.00, 30.00));
+    table.insert("gpt-3.5-turbo".to_string(), ModelPricing::new(0.50, 1.50));
+    table
+}